@@ -0,0 +1,79 @@
+//! Compares re-composing a 1,000-row list with [`spawn`] and [`spawn_diff`].
+//!
+//! Run with `cargo bench --bench diff`.
+
+use actuate::prelude::{Mut, *};
+use bevy::{ecs::component::Tick, prelude::*};
+use bevy_mod_actuate::prelude::*;
+use std::time::{Duration, Instant};
+
+const ROWS: usize = 1_000;
+const FRAMES: u32 = 100;
+
+#[derive(Component, Clone, PartialEq)]
+struct Row(usize);
+
+#[derive(Component, Clone, PartialEq)]
+struct Label(String);
+
+#[derive(Data)]
+struct List {
+    diff: bool,
+}
+
+impl Compose for List {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let frame = use_mut(&cx, || 0);
+
+        // Re-compose the list every frame without changing any rows.
+        use_world(&cx, move |_: &World| {
+            Mut::update(frame, |frame| *frame += 1)
+        });
+
+        let diff = cx.me().diff;
+        compose::from_iter((0..ROWS).collect::<Vec<_>>(), move |index| {
+            let bundle = (Row(*index), Label(format!("Row {}", *index)));
+            if diff {
+                spawn_diff(bundle)
+            } else {
+                spawn(bundle)
+            }
+        })
+    }
+}
+
+fn last_changed(app: &mut App) -> Vec<Tick> {
+    app.world_mut()
+        .query::<(bevy::prelude::Ref<Row>, bevy::prelude::Ref<Label>)>()
+        .iter(app.world())
+        .flat_map(|(row, label)| [row.last_changed(), label.last_changed()])
+        .collect()
+}
+
+fn run(diff: bool) -> (Duration, usize) {
+    let mut app = App::new();
+    app.add_plugins(ActuatePlugin::default());
+    app.world_mut().spawn(Composition::new(List { diff }));
+
+    app.update();
+    app.update();
+
+    let mut changed = 0;
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        let before = last_changed(&mut app);
+        app.update();
+        let after = last_changed(&mut app);
+
+        changed += before.iter().zip(&after).filter(|(a, b)| a != b).count();
+    }
+
+    (start.elapsed() / FRAMES, changed / FRAMES as usize)
+}
+
+fn main() {
+    for (name, diff) in [("spawn", false), ("spawn_diff", true)] {
+        let (frame_time, changed) = run(diff);
+        println!("{name:<10} {frame_time:>12?}/frame {changed:>6} changed components/frame");
+    }
+}
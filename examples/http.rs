@@ -98,13 +98,13 @@ impl Compose for Example {
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, ActuatePlugin))
+        .add_plugins((DefaultPlugins, ActuatePlugin::default()))
         .add_systems(Startup, setup)
         .run();
 }
 
 fn setup(mut commands: Commands) {
-    commands.spawn(Camera2d::default());
+    commands.spawn(Camera2d);
 
     // Spawn a composition with a `BreedList`, adding it to the Actuate runtime.
     commands.spawn((Node::default(), Composition::new(Example)));
@@ -0,0 +1,698 @@
+//! Debugging of compositions.
+//!
+//! [`inspect`] reports the state of each [`Composition`](crate::Composition) in a world,
+//! including the entity each [`Spawn`] owns and how many times it has been composed.
+//! [`DebugPlugin`] enables this tracking, keeps the report in the [`CompositionTree`] resource,
+//! and can log it whenever it changes.
+//!
+//! Composables are reported by the content they're composed in:
+//! the content of each composition, and the content of each [`Spawn`].
+//! Each composable's type name and compose count is recorded where its scope is composed,
+//! and a re-composition of any composable in that content (other than in a nested [`Spawn`])
+//! is counted as a re-composition of the content.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_mod_actuate::{debug::DebugPlugin, prelude::*};
+//!
+//! App::new()
+//!     .add_plugins((
+//!         DefaultPlugins,
+//!         ActuatePlugin::default(),
+//!         DebugPlugin::default().with_log(true),
+//!     ))
+//!     .run();
+//! ```
+
+use crate::{ActuateSet, Runtime, Spawn};
+use actuate::prelude::*;
+use bevy::{
+    app::{App, Plugin},
+    ecs::schedule::{InternedScheduleLabel, IntoSystemConfigs, ScheduleLabel},
+    log::info,
+    prelude::{Entity, Resource, World},
+    utils::{
+        tracing::{
+            dispatcher::{self, Dispatch},
+            field::{Field, Visit},
+            level_filters::LevelFilter,
+            span,
+            subscriber::Interest,
+            Event, Metadata, Subscriber,
+        },
+        HashMap,
+    },
+};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    fmt,
+    rc::Rc,
+};
+
+/// Debug information about a [`Composition`](crate::Composition).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompositionInfo {
+    /// Entity of the composition.
+    pub entity: Entity,
+
+    /// Type name of the composition's content.
+    pub name: &'static str,
+
+    /// Number of frames in which the composition's content was composed.
+    pub composes: usize,
+
+    /// Number of world listeners registered by this composition (see [`use_world`](crate::use_world)).
+    pub listeners: usize,
+
+    /// Composables in the composition's content, excluding the content of each [`Spawn`].
+    pub composables: Vec<ComposableInfo>,
+
+    /// Spawned entities at the top of this composition.
+    pub spawns: Vec<SpawnInfo>,
+}
+
+/// Debug information about a [`Spawn`] composable.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpawnInfo {
+    /// Spawned entity.
+    pub entity: Entity,
+
+    /// Type name of the spawned bundle.
+    pub bundle: &'static str,
+
+    /// Number of times this composable has been composed.
+    pub composes: usize,
+
+    /// Type name of the content composed as children of the spawned entity.
+    pub content: &'static str,
+
+    /// Number of frames in which the content of this composable was composed.
+    pub content_composes: usize,
+
+    /// Number of world listeners registered by the content of this composable,
+    /// excluding those registered by other [`Spawn`] composables in its content.
+    pub listeners: usize,
+
+    /// Composables in the content of this composable, excluding the content of each nested [`Spawn`].
+    pub composables: Vec<ComposableInfo>,
+
+    /// Spawned children of this entity, in the order they were first composed.
+    pub children: Vec<SpawnInfo>,
+}
+
+/// Debug information about the composables of a type in some content.
+///
+/// Composables of the same type in the same content are reported together,
+/// in the order each type was first composed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComposableInfo {
+    /// Type name of the composable, without its module path or generics.
+    pub name: String,
+
+    /// Number of times composables of this type have been composed.
+    pub composes: usize,
+}
+
+/// Write the `composables` line of some content, if any were composed.
+fn fmt_composables(
+    f: &mut fmt::Formatter<'_>,
+    composables: &[ComposableInfo],
+    indent: usize,
+) -> fmt::Result {
+    if composables.is_empty() {
+        return Ok(());
+    }
+
+    write!(f, "{:indent$}composables: ", "")?;
+    for (idx, composable) in composables.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{} ({})", composable.name, composable.composes)?;
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for CompositionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} {}: composes: {}, listeners: {}",
+            self.name, self.entity, self.composes, self.listeners
+        )?;
+        fmt_composables(f, &self.composables, 2)?;
+
+        for spawn in &self.spawns {
+            spawn.fmt_indented(f, 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl SpawnInfo {
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{} {}: composes: {}, listeners: {}",
+            "",
+            self.bundle,
+            self.entity,
+            self.composes,
+            self.listeners,
+            indent = depth * 4
+        )?;
+        writeln!(
+            f,
+            "{:indent$}content {}: composes: {}",
+            "",
+            self.content,
+            self.content_composes,
+            indent = depth * 4 + 2
+        )?;
+        fmt_composables(f, &self.composables, depth * 4 + 4)?;
+
+        for child in &self.children {
+            child.fmt_indented(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SpawnInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+/// Inspect each [`Composition`](crate::Composition) in the world, sorted by entity.
+///
+/// Compositions are only tracked once the [`DebugPlugin`] has been added,
+/// so this returns an empty list without it.
+///
+/// # Panics
+/// Panics if called during composition.
+pub fn inspect(world: &World) -> Vec<CompositionInfo> {
+    let Some(rt) = world.get_non_send_resource::<Runtime>() else {
+        return Vec::new();
+    };
+
+    let mut compositions: Vec<_> = rt
+        .composers
+        .borrow()
+        .iter()
+        .filter_map(|(entity, rt_composer)| Some(rt_composer.debug.as_ref()?.info(*entity)))
+        .collect();
+    compositions.sort_by_key(|composition| composition.entity);
+    compositions
+}
+
+/// Resource containing the latest [`inspect`] report of each [`Composition`](crate::Composition).
+///
+/// This is updated by the [`DebugPlugin`] after each composition.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct CompositionTree {
+    /// Debug information about each composition, sorted by entity.
+    pub compositions: Vec<CompositionInfo>,
+}
+
+impl fmt::Display for CompositionTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for composition in &self.compositions {
+            composition.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Debug plugin to inspect [`Composition`](crate::Composition)s.
+///
+/// This enables the tracking of compositions spawned after it's added,
+/// updates the [`CompositionTree`] resource after each composition,
+/// and can optionally log it whenever it changes.
+///
+/// The [`ActuatePlugin`](crate::ActuatePlugin) must be added first,
+/// and the schedule must match its schedule.
+pub struct DebugPlugin {
+    schedule: InternedScheduleLabel,
+    log: bool,
+}
+
+impl DebugPlugin {
+    /// Create a new plugin that runs in the [`Update`](bevy::app::Update) schedule, without logging.
+    pub fn new() -> Self {
+        Self {
+            schedule: bevy::app::Update.intern(),
+            log: false,
+        }
+    }
+
+    /// Get the schedule to inspect compositions in.
+    pub fn schedule(&self) -> InternedScheduleLabel {
+        self.schedule
+    }
+
+    /// Set the schedule to inspect compositions in.
+    pub fn set_schedule(&mut self, schedule: impl ScheduleLabel) {
+        self.schedule = schedule.intern();
+    }
+
+    /// Set the schedule to inspect compositions in.
+    pub fn with_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+
+    /// Returns `true` if the [`CompositionTree`] is logged when it changes.
+    pub fn log(&self) -> bool {
+        self.log
+    }
+
+    /// Set whether the [`CompositionTree`] is logged when it changes.
+    pub fn set_log(&mut self, log: bool) {
+        self.log = log;
+    }
+
+    /// Set whether the [`CompositionTree`] is logged when it changes.
+    pub fn with_log(mut self, log: bool) -> Self {
+        self.log = log;
+        self
+    }
+}
+
+impl Default for DebugPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        let log = self.log;
+
+        app.world()
+            .get_non_send_resource::<Runtime>()
+            .expect("The `ActuatePlugin` must be added before the `DebugPlugin`.")
+            .is_debug
+            .set(true);
+
+        app.init_resource::<CompositionTree>().add_systems(
+            self.schedule,
+            (move |world: &mut World| update_tree(world, log)).after(ActuateSet::FlushCommands),
+        );
+    }
+}
+
+fn update_tree(world: &mut World, log: bool) {
+    let tree = CompositionTree {
+        compositions: inspect(world),
+    };
+
+    let mut current = world.resource_mut::<CompositionTree>();
+    if *current != tree {
+        if log {
+            info!("Compositions:\n{tree}");
+        }
+        *current = tree;
+    }
+}
+
+/// Debug state of a composition, shared with its composables.
+///
+/// This is only created if the [`DebugPlugin`] has been added.
+pub(crate) struct DebugState {
+    name: &'static str,
+    /// Number of times the composition has been composed, so each re-composition of content is only counted once.
+    pass: Cell<u64>,
+    content: ContentNode,
+    spawns: RefCell<BTreeMap<u64, SpawnNode>>,
+    next_spawn: Cell<u64>,
+    /// Number of listeners registered by the content of each spawned entity (or the composition's target).
+    listeners: RefCell<HashMap<Entity, usize>>,
+}
+
+/// Re-composition counts of the content of a composition or [`Spawn`].
+#[derive(Default)]
+struct ContentNode {
+    composes: Cell<usize>,
+    last_pass: Cell<Option<u64>>,
+    composables: RefCell<Vec<ComposableInfo>>,
+}
+
+impl ContentNode {
+    fn on_compose(&self, pass: u64, name: &str) {
+        if self.last_pass.replace(Some(pass)) != Some(pass) {
+            self.composes.set(self.composes.get() + 1);
+        }
+
+        let mut composables = self.composables.borrow_mut();
+        match composables
+            .iter_mut()
+            .find(|composable| composable.name == name)
+        {
+            Some(composable) => composable.composes += 1,
+            None => composables.push(ComposableInfo {
+                name: name.to_owned(),
+                composes: 1,
+            }),
+        }
+    }
+}
+
+struct SpawnNode {
+    entity: Entity,
+    parent: Option<Entity>,
+    bundle: &'static str,
+    content_name: &'static str,
+    content: ContentNode,
+    composes: usize,
+}
+
+impl DebugState {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            pass: Cell::new(0),
+            content: ContentNode::default(),
+            spawns: RefCell::new(BTreeMap::new()),
+            next_spawn: Cell::new(0),
+            listeners: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Count the composition of a composable named `name` in the content of `owner`.
+    fn on_compose(&self, owner: Option<u64>, name: &str) {
+        let pass = self.pass.get();
+        match owner {
+            Some(key) => {
+                if let Some(node) = self.spawns.borrow().get(&key) {
+                    node.content.on_compose(pass, name);
+                }
+            }
+            None => self.content.on_compose(pass, name),
+        }
+    }
+
+    fn info(&self, entity: Entity) -> CompositionInfo {
+        let spawns = self.spawns.borrow();
+        let listeners = self.listeners.borrow();
+
+        let mut children: HashMap<Option<Entity>, Vec<&SpawnNode>> = HashMap::new();
+        for node in spawns.values() {
+            let parent = node
+                .parent
+                .filter(|parent| spawns.values().any(|node| node.entity == *parent));
+            children.entry(parent).or_default().push(node);
+        }
+
+        fn spawn_infos(
+            parent: Option<Entity>,
+            children: &HashMap<Option<Entity>, Vec<&SpawnNode>>,
+            listeners: &HashMap<Entity, usize>,
+        ) -> Vec<SpawnInfo> {
+            children
+                .get(&parent)
+                .into_iter()
+                .flatten()
+                .map(|node| SpawnInfo {
+                    entity: node.entity,
+                    bundle: node.bundle,
+                    composes: node.composes,
+                    content: node.content_name,
+                    content_composes: node.content.composes.get(),
+                    listeners: listeners.get(&node.entity).copied().unwrap_or_default(),
+                    composables: node.content.composables.borrow().clone(),
+                    children: spawn_infos(Some(node.entity), children, listeners),
+                })
+                .collect()
+        }
+
+        CompositionInfo {
+            entity,
+            name: self.name,
+            composes: self.content.composes.get(),
+            listeners: listeners.values().sum(),
+            composables: self.content.composables.borrow().clone(),
+            spawns: spawn_infos(None, &children, &listeners),
+        }
+    }
+}
+
+/// Debug context of the content of a composition or [`Spawn`].
+#[derive(Clone)]
+pub(crate) struct DebugContext {
+    state: Rc<DebugState>,
+    /// Key of the [`Spawn`] that owns this content, or `None` for the composition's content.
+    owner: Option<u64>,
+}
+
+impl DebugContext {
+    pub(crate) fn new(state: Rc<DebugState>) -> Self {
+        Self { state, owner: None }
+    }
+
+    /// Composable to attribute the composables after it to this content.
+    pub(crate) fn enter_probe(&self) -> OwnerProbe {
+        OwnerProbe::Enter(self.owner)
+    }
+}
+
+/// Track a [`Spawn`] composable in its composition's debug state,
+/// returning the debug context of its content.
+pub(crate) fn use_debug_spawn<C>(
+    cx: ScopeState<'_>,
+    debug: &DebugContext,
+    spawn: &Spawn<'_, C>,
+    entity: Entity,
+    parent: Option<Entity>,
+) -> DebugContext {
+    let debug = use_ref(cx, || debug.clone());
+
+    let key = *use_ref(cx, || {
+        let key = debug.state.next_spawn.get();
+        debug.state.next_spawn.set(key + 1);
+        debug.state.spawns.borrow_mut().insert(
+            key,
+            SpawnNode {
+                entity,
+                parent,
+                bundle: spawn.bundle_name,
+                content_name: std::any::type_name::<C>(),
+                content: ContentNode::default(),
+                composes: 0,
+            },
+        );
+        key
+    });
+
+    if let Some(node) = debug.state.spawns.borrow_mut().get_mut(&key) {
+        node.entity = entity;
+        node.composes += 1;
+    }
+
+    use_drop(cx, move || {
+        debug.state.spawns.borrow_mut().remove(&key);
+    });
+
+    DebugContext {
+        state: debug.state.clone(),
+        owner: Some(key),
+    }
+}
+
+/// Track a world listener in its composition's debug state, owned by the content of `owner`.
+pub(crate) fn use_debug_listener(cx: ScopeState<'_>, debug: &DebugContext, owner: Entity) {
+    let debug = use_ref(cx, || {
+        *debug.state.listeners.borrow_mut().entry(owner).or_default() += 1;
+        debug.state.clone()
+    });
+
+    use_drop(cx, move || {
+        let mut listeners = debug.listeners.borrow_mut();
+        if let Some(count) = listeners.get_mut(&owner) {
+            *count -= 1;
+            if *count == 0 {
+                listeners.remove(&owner);
+            }
+        }
+    });
+}
+
+/// Composable to mark where the content of a [`Spawn`] starts and ends in its composition.
+///
+/// Composables are composed in order, so each composable between these probes is
+/// attributed to the content they surround. This is re-composed every frame.
+pub(crate) enum OwnerProbe {
+    /// Start the content of a [`Spawn`] (or the composition's content if `None`).
+    Enter(Option<u64>),
+    /// End the content started by the last [`OwnerProbe::Enter`].
+    Exit,
+}
+
+unsafe impl Data for OwnerProbe {}
+
+impl Compose for OwnerProbe {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.set_changed();
+
+        RECORDING.with_borrow_mut(|recording| {
+            if let Some(recording) = recording {
+                match *cx.me() {
+                    OwnerProbe::Enter(owner) => recording.owners.push(owner),
+                    OwnerProbe::Exit => {
+                        recording.owners.pop();
+                    }
+                }
+            }
+        });
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
+    }
+}
+
+/// Composition pass being recorded on this thread.
+struct Recording {
+    state: Rc<DebugState>,
+    /// Owners of the content being composed, innermost last.
+    owners: Vec<Option<u64>>,
+    /// Dispatcher to forward all other traces to.
+    prev: Dispatch,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+
+    // Creating a dispatcher re-registers every callsite, so only do so once per thread.
+    static RECORDER: Dispatch = Dispatch::new(ComposeRecorder);
+}
+
+/// Run `f` to compose a composition, recording each composable composed in it.
+///
+/// Actuate traces the name of each composable right after composing it (before its children),
+/// so these traces are attributed to the content being composed.
+pub(crate) fn record<R>(state: &Rc<DebugState>, f: impl FnOnce() -> R) -> R {
+    struct Guard(Option<Recording>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            RECORDING.with(|recording| *recording.borrow_mut() = prev);
+        }
+    }
+
+    state.pass.set(state.pass.get() + 1);
+
+    let recording = Recording {
+        state: state.clone(),
+        owners: Vec::new(),
+        prev: dispatcher::get_default(Dispatch::clone),
+    };
+    let _guard = Guard(RECORDING.replace(Some(recording)));
+
+    RECORDER.with(|recorder| dispatcher::with_default(recorder, f))
+}
+
+/// Subscriber to record compose traces, forwarding everything else to the previous dispatcher.
+struct ComposeRecorder;
+
+impl ComposeRecorder {
+    fn is_compose(metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "actuate::compose"
+    }
+
+    fn prev() -> Dispatch {
+        RECORDING
+            .with_borrow(|recording| recording.as_ref().map(|recording| recording.prev.clone()))
+            .unwrap_or_else(|| dispatcher::get_default(Dispatch::clone))
+    }
+}
+
+impl Subscriber for ComposeRecorder {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if Self::is_compose(metadata) {
+            Interest::always()
+        } else {
+            Interest::sometimes()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::TRACE)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        Self::is_compose(metadata) || Self::prev().enabled(metadata)
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        Self::prev().new_span(span)
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        Self::prev().record(span, values)
+    }
+
+    fn record_follows_from(&self, span: &span::Id, follows: &span::Id) {
+        Self::prev().record_follows_from(span, follows)
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        if !Self::is_compose(event.metadata()) {
+            Self::prev().event(event);
+            return;
+        }
+
+        struct Message(Option<String>);
+
+        impl Visit for Message {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if field.name() == "message" {
+                    self.0 = Some(format!("{value:?}"));
+                }
+            }
+        }
+
+        let mut message = Message(None);
+        event.record(&mut message);
+        let Some(name) = message
+            .0
+            .as_deref()
+            .and_then(|message| message.strip_prefix("Compose: "))
+        else {
+            return;
+        };
+
+        let Some((state, owner)) = RECORDING.with_borrow(|recording| {
+            recording.as_ref().map(|recording| {
+                (
+                    recording.state.clone(),
+                    recording.owners.last().copied().flatten(),
+                )
+            })
+        }) else {
+            return;
+        };
+        state.on_compose(owner, name);
+    }
+
+    fn enter(&self, span: &span::Id) {
+        Self::prev().enter(span)
+    }
+
+    fn exit(&self, span: &span::Id) {
+        Self::prev().exit(span)
+    }
+
+    fn clone_span(&self, id: &span::Id) -> span::Id {
+        Self::prev().clone_span(id)
+    }
+
+    fn try_close(&self, id: span::Id) -> bool {
+        Self::prev().try_close(id)
+    }
+}
@@ -24,7 +24,7 @@
 //!
 //! fn main() {
 //!     App::new()
-//!         .add_plugins((DefaultPlugins, ActuatePlugin))
+//!         .add_plugins((DefaultPlugins, ActuatePlugin::default()))
 //!         .add_systems(Startup, setup)
 //!         .run();
 //! }
@@ -40,66 +40,201 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 use actuate::{
-    composer::{Composer, Update, Updater},
+    composer::{self, Composer, Update, Updater},
     prelude::*,
-    use_callback,
+    use_callback, Memoize,
 };
 use bevy::{
     app::Plugin,
     ecs::{
-        component::{ComponentHooks, StorageType},
-        system::{SystemParam, SystemParamItem, SystemState},
+        component::{ComponentHooks, ComponentId, StorageType, Tick},
+        entity::Entities,
+        event::Events,
+        observer::TriggerTargets,
+        query::{FilteredAccess, QueryFilter, QueryItem, ReadOnlyQueryData},
+        schedule::{
+            Condition, InternedScheduleLabel, IntoSystemConfigs, IntoSystemSetConfigs,
+            ScheduleLabel, SystemSet,
+        },
+        system::{
+            System, SystemChangeTick, SystemId, SystemInput, SystemParam, SystemParamItem,
+            SystemState,
+        },
         world::CommandQueue,
     },
+    hierarchy::DespawnRecursiveExt,
     prelude::{
-        App, BuildChildren, Bundle, Command, Component, Entity, EntityWorldMut, Event, In,
-        ParamSet, Trigger, World,
+        App, BuildChildren, Bundle, Children, Command, Component, DetectChanges, Entity,
+        EntityWorldMut, Event, In, IntoSystem, Observer, ParamSet, Parent, Query, Res, Resource,
+        Trigger, World,
     },
-    utils::HashMap,
+    state::state::{FreelyMutableState, NextState, State, States},
+    utils::{HashMap, HashSet},
 };
-use slotmap::{DefaultKey, SlotMap};
+use debug::{DebugContext, DebugState};
 use std::{
+    any::{self, Any},
+    borrow::Cow,
     cell::{Cell, RefCell},
+    cmp::Reverse,
+    collections::BTreeMap,
+    hash::Hash,
     marker::PhantomData,
-    mem, ptr,
+    mem::{self, ManuallyDrop},
+    ops::Deref,
+    ptr::NonNull,
     rc::Rc,
-    sync::{mpsc, Arc},
+    sync::{mpsc, Arc, OnceLock},
+    thread::{self, ThreadId},
 };
 use tokio::sync::RwLockWriteGuard;
 
+pub mod debug;
+
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
 /// Prelude of common items.
 pub mod prelude {
     pub use crate::{
-        spawn, spawn_with, use_bundle, use_world, ActuatePlugin, Composition, UseWorld,
+        match_state, spawn, spawn_diff, spawn_diff_with, spawn_keyed_list, spawn_with,
+        use_bevy_state, use_bound_component, use_bundle, use_component, use_event_writer,
+        use_events, use_query, use_resource, use_resource_mut, use_system, use_world, use_world_if,
+        use_world_if_with_priority, use_world_memo, use_world_with_priority, ActuatePlugin,
+        ActuateSet, Composition, DiffBundle, UseWorld,
     };
 }
 
 /// Actuate plugin to run [`Composition`]s.
-pub struct ActuatePlugin;
+///
+/// By default, compositions are run in the [`Update`](bevy::app::Update) schedule.
+/// Each step of a composition runs in an [`ActuateSet`], which can be ordered against other systems.
+///
+/// ```no_run
+/// use bevy::{prelude::*, ui::UiSystem};
+/// use bevy_mod_actuate::prelude::*;
+///
+/// App::new()
+///     .add_plugins((
+///         DefaultPlugins,
+///         ActuatePlugin::default().with_schedule(PostUpdate),
+///     ))
+///     .configure_sets(PostUpdate, ActuateSet::FlushCommands.before(UiSystem::Layout));
+/// ```
+pub struct ActuatePlugin {
+    schedule: InternedScheduleLabel,
+}
+
+impl ActuatePlugin {
+    /// Create a new plugin that runs compositions in the [`Update`](bevy::app::Update) schedule.
+    pub fn new() -> Self {
+        Self {
+            schedule: bevy::app::Update.intern(),
+        }
+    }
+
+    /// Get the schedule to run compositions in.
+    pub fn schedule(&self) -> InternedScheduleLabel {
+        self.schedule
+    }
+
+    /// Set the schedule to run compositions in.
+    pub fn set_schedule(&mut self, schedule: impl ScheduleLabel) {
+        self.schedule = schedule.intern();
+    }
+
+    /// Set the schedule to run compositions in.
+    pub fn with_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+}
+
+impl Default for ActuatePlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// System sets for the steps of running [`Composition`]s.
+///
+/// These sets are chained in the order they're declared.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ActuateSet {
+    /// Run the listeners registered with [`use_world`].
+    ///
+    /// Listeners run in order of their priority (see [`use_world_with_priority`]),
+    /// and then in the order they were registered.
+    RunListeners,
+    /// Compose each [`Composition`].
+    Compose,
+    /// Apply the state updates queued during composition.
+    ApplyUpdates,
+    /// Flush the commands queued with [`use_commands`].
+    FlushCommands,
+}
 
 impl Plugin for ActuatePlugin {
     fn build(&self, app: &mut App) {
         let (tx, rx) = mpsc::channel();
         let rt = Runtime {
-            composers: RefCell::new(HashMap::new()),
-            lock: None,
+            composers: Rc::new(RefCell::new(HashMap::new())),
+            pending: Rc::new(RefCell::new(Vec::new())),
+            cx: RuntimeContext {
+                inner: Rc::new(RefCell::new(Inner {
+                    world: None,
+                    listeners: BTreeMap::new(),
+                    next_listener_order: 0,
+                    updates: Vec::new(),
+                    commands: Rc::new(RefCell::new(CommandQueue::default())),
+                })),
+            },
             tx,
-            rx,
+            rx: Rc::new(rx),
+            is_debug: Rc::new(Cell::new(false)),
         };
 
         app.insert_non_send_resource(rt)
-            .add_systems(bevy::prelude::Update, compose);
+            .configure_sets(
+                self.schedule,
+                (
+                    ActuateSet::RunListeners,
+                    ActuateSet::Compose,
+                    ActuateSet::ApplyUpdates,
+                    ActuateSet::FlushCommands,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                self.schedule,
+                (
+                    run_listeners.in_set(ActuateSet::RunListeners),
+                    compose.in_set(ActuateSet::Compose),
+                    apply_updates.in_set(ActuateSet::ApplyUpdates),
+                    flush_commands.in_set(ActuateSet::FlushCommands),
+                ),
+            );
     }
 }
 
-type UpdateFn = Box<dyn FnMut(&mut World)>;
+type UpdateFn = Box<dyn FnOnce(&mut World)>;
 
 type WorldListenerFn = Rc<dyn Fn(&mut World)>;
 
+/// Key of a world listener, sorting by descending priority and then by registration order.
+type ListenerKey = (Reverse<i32>, u64);
+
 struct Inner {
-    world_ptr: *mut World,
-    listeners: SlotMap<DefaultKey, WorldListenerFn>,
-    updates: Vec<UpdateFn>,
+    /// The world of this runtime, if it's available to be borrowed.
+    ///
+    /// This is only set within [`RuntimeContext::scope`],
+    /// and is taken out while borrowed by [`RuntimeContext::with_world`].
+    world: Option<NonNull<World>>,
+    listeners: BTreeMap<ListenerKey, WorldListenerFn>,
+    next_listener_order: u64,
+    /// Updates to apply after composition, with the entity of the composition whose state they borrow (if any).
+    updates: Vec<(Option<Entity>, UpdateFn)>,
     commands: Rc<RefCell<CommandQueue>>,
 }
 
@@ -119,22 +254,99 @@ impl RuntimeContext {
         })
     }
 
-    unsafe fn world_mut(&self) -> &'static mut World {
-        &mut *self.inner.borrow().world_ptr
+    /// Run `f` within this runtime context, making `world` available to [`RuntimeContext::with_world`].
+    fn scope<R>(&self, world: &mut World, f: impl FnOnce() -> R) -> R {
+        struct Guard<'a> {
+            rt_cx: &'a RuntimeContext,
+            last: Option<RuntimeContext>,
+        }
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.rt_cx.inner.borrow_mut().world = None;
+                RUNTIME_CONTEXT.with(|cell| *cell.borrow_mut() = self.last.take());
+            }
+        }
+
+        // `world` is only accessed through this pointer until the guard is dropped.
+        self.inner.borrow_mut().world = Some(NonNull::from(world));
+        let last = RUNTIME_CONTEXT.with(|cell| cell.borrow_mut().replace(self.clone()));
+        let _guard = Guard { rt_cx: self, last };
+
+        f()
+    }
+
+    /// Queue `f` to run with the world after composition.
+    fn queue_update(&self, f: impl FnOnce(&mut World) + 'static) {
+        self.inner.borrow_mut().updates.push((None, Box::new(f)));
+    }
+
+    /// Run `f` with exclusive access to the world.
+    ///
+    /// # Panics
+    /// Panics if called outside of [`RuntimeContext::scope`], or if the world is already borrowed.
+    fn with_world<R>(&self, f: impl FnOnce(&mut World) -> R) -> R {
+        self.try_with_world(f).expect(
+            "The world is not available (it's either already borrowed or accessed outside of composition).",
+        )
+    }
+
+    /// Reserve an entity to spawn when the command queue is next flushed,
+    /// returning `None` if the world's entities are unavailable (e.g. while the world is borrowed).
+    fn try_reserve_entity(&self) -> Option<Entity> {
+        if let Some(entities) = ENTITIES.with(Cell::get) {
+            // Safety: The pointer is only set by `with_entities`, so it's borrowed for as long as it's set.
+            return Some(unsafe { entities.as_ref() }.reserve_entity());
+        }
+
+        self.try_with_world(|world| world.entities().reserve_entity())
+    }
+
+    /// Run `f` with exclusive access to the world, returning `None` if the world is unavailable.
+    fn try_with_world<R>(&self, f: impl FnOnce(&mut World) -> R) -> Option<R> {
+        let mut world = self.inner.borrow_mut().world.take()?;
+
+        // Safety: The pointer is only set during `RuntimeContext::scope`, where its `&mut World` is otherwise unused,
+        // and it's taken out of this context while borrowed so no other references to the world can exist.
+        let output = f(unsafe { world.as_mut() });
+
+        self.inner.borrow_mut().world = Some(world);
+        Some(output)
     }
 }
 
 thread_local! {
+    /// The runtime context of the world currently being composed.
     static RUNTIME_CONTEXT: RefCell<Option<RuntimeContext>> = const { RefCell::new(None) };
+
+    /// The entities of the world currently running a world listener or observer (see [`with_entities`]).
+    static ENTITIES: Cell<Option<NonNull<Entities>>> = const { Cell::new(None) };
+}
+
+/// Run `f` with `entities` available to reserve entities from (see [`RuntimeContext::reserve_entity`]).
+///
+/// World listeners and observers borrow the world, so they fetch its entities to reserve entities from instead.
+fn with_entities<R>(entities: &Entities, f: impl FnOnce() -> R) -> R {
+    struct Guard(Option<NonNull<Entities>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            ENTITIES.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _guard = Guard(ENTITIES.with(|cell| cell.replace(Some(NonNull::from(entities)))));
+    f()
 }
 
 struct RuntimeUpdater {
-    queue: mpsc::Sender<Update>,
+    entity: Entity,
+    queue: mpsc::Sender<(Entity, Update)>,
 }
 
 impl Updater for RuntimeUpdater {
     fn update(&self, update: Update) {
-        self.queue.send(update).unwrap();
+        self.queue.send((self.entity, update)).unwrap();
     }
 }
 
@@ -143,15 +355,82 @@ unsafe impl Send for RuntimeUpdater {}
 unsafe impl Sync for RuntimeUpdater {}
 
 struct RuntimeComposer {
-    composer: Composer,
+    // The guard borrows from the composer's lock, so it must be dropped first.
     guard: Option<RwLockWriteGuard<'static, ()>>,
+    composer: Composer,
+    /// Debug state of this composition, if the [`DebugPlugin`](debug::DebugPlugin) has been added.
+    debug: Option<Rc<DebugState>>,
 }
 
+type PendingComposer = (Entity, Option<RuntimeComposer>);
+
+/// Runtime for the compositions of a world.
+///
+/// This is cloned out of the world by each system,
+/// so no borrows of the world are held while composables access it.
+#[derive(Clone)]
 struct Runtime {
-    composers: RefCell<HashMap<Entity, RuntimeComposer>>,
-    lock: Option<RwLockWriteGuard<'static, ()>>,
-    tx: mpsc::Sender<Update>,
-    rx: mpsc::Receiver<Update>,
+    composers: Rc<RefCell<HashMap<Entity, RuntimeComposer>>>,
+    /// Composers to insert (or remove, if `None`) before the next composition.
+    pending: Rc<RefCell<Vec<PendingComposer>>>,
+    /// Runtime context for this runtime's world.
+    cx: RuntimeContext,
+    tx: mpsc::Sender<(Entity, Update)>,
+    rx: Rc<mpsc::Receiver<(Entity, Update)>>,
+    /// `true` if compositions are tracked for the [`DebugPlugin`](debug::DebugPlugin).
+    is_debug: Rc<Cell<bool>>,
+}
+
+impl Runtime {
+    fn get(world: &World) -> Self {
+        world.non_send_resource::<Self>().clone()
+    }
+
+    fn apply_updates(&self) {
+        while let Ok((_, update)) = self.rx.try_recv() {
+            unsafe { update.apply() }
+        }
+    }
+
+    /// Insert or remove any pending composers.
+    ///
+    /// Removed composers are dropped here, running their `use_drop` hooks
+    /// (which despawns their spawned entities and unregisters their world listeners).
+    fn apply_pending(&self) {
+        loop {
+            let pending = mem::take(&mut *self.pending.borrow_mut());
+            if pending.is_empty() {
+                break;
+            }
+
+            for (entity, composer) in pending {
+                let old = if let Some(composer) = composer {
+                    self.composers.borrow_mut().insert(entity, composer)
+                } else {
+                    self.composers.borrow_mut().remove(&entity)
+                };
+
+                if old.is_some() {
+                    // Discard any updates queued by the old composer, as its state is about to be dropped.
+                    let updates: Vec<_> = self.rx.try_iter().collect();
+                    for (update_entity, update) in updates {
+                        if update_entity != entity {
+                            self.tx.send((update_entity, update)).unwrap();
+                        }
+                    }
+
+                    let updates = mem::take(&mut self.cx.inner.borrow_mut().updates);
+                    let updates: Vec<_> = updates
+                        .into_iter()
+                        .filter(|(update_entity, _)| *update_entity != Some(entity))
+                        .collect();
+                    self.cx.inner.borrow_mut().updates = updates;
+                }
+
+                drop(old);
+            }
+        }
+    }
 }
 
 /// Composition of some composable content.
@@ -198,16 +477,42 @@ where
 #[derive(Data)]
 struct CompositionContent<C> {
     content: C,
+    entity: Entity,
     target: Entity,
+    debug: Option<Rc<DebugState>>,
+}
+
+/// Context of the [`Composition`] a scope belongs to.
+struct CompositionContext {
+    entity: Entity,
 }
 
 impl<C: Compose> Compose for CompositionContent<C> {
     fn compose(cx: Scope<Self>) -> impl Compose {
+        let child_order = use_ref(&cx, Rc::<ChildOrder>::default);
+
+        use_provider(&cx, || CompositionContext {
+            entity: cx.me().entity,
+        });
+
         use_provider(&cx, || SpawnContext {
             parent_entity: cx.me().target,
+            child_order: child_order.clone(),
+            keyed_entities: None,
+            debug: cx.me().debug.clone().map(DebugContext::new),
         });
 
-        Ref::map(cx.me(), |me| &me.content)
+        (
+            ChildOrderProbe {
+                child_order: child_order.clone(),
+                parent: None,
+            },
+            Ref::map(cx.me(), |me| &me.content),
+        )
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
     }
 }
 
@@ -220,81 +525,118 @@ where
     fn register_component_hooks(hooks: &mut ComponentHooks) {
         hooks.on_insert(|mut world, entity, _| {
             world.commands().queue(move |world: &mut World| {
-                let mut composition = world.get_mut::<Composition<C>>(entity).unwrap();
+                // The composition may have been removed before this command was applied.
+                let Some(mut composition) = world.get_mut::<Composition<C>>(entity) else {
+                    return;
+                };
 
-                let content = composition.content.take().unwrap();
+                let Some(content) = composition.content.take() else {
+                    return;
+                };
 
                 let target = composition.target.unwrap_or(entity);
 
-                let tx = world.non_send_resource::<Runtime>().tx.clone();
+                let rt = world.non_send_resource::<Runtime>();
+                let debug = rt
+                    .is_debug
+                    .get()
+                    .then(|| Rc::new(DebugState::new(any::type_name::<C>())));
 
-                let rt = world.non_send_resource_mut::<Runtime>();
-                rt.composers.borrow_mut().insert(
+                rt.pending.borrow_mut().push((
                     entity,
-                    RuntimeComposer {
+                    Some(RuntimeComposer {
                         composer: Composer::with_updater(
-                            CompositionContent { content, target },
-                            RuntimeUpdater { queue: tx },
+                            CompositionContent {
+                                content,
+                                entity,
+                                target,
+                                debug: debug.clone(),
+                            },
+                            RuntimeUpdater {
+                                entity,
+                                queue: rt.tx.clone(),
+                            },
                         ),
                         guard: None,
-                    },
-                );
+                        debug,
+                    }),
+                ));
+            });
+        });
+
+        hooks.on_replace(|mut world, entity, _| {
+            world.commands().queue(move |world: &mut World| {
+                let rt = world.non_send_resource::<Runtime>();
+                rt.pending.borrow_mut().push((entity, None));
             });
         });
     }
 }
 
-fn compose(world: &mut World) {
-    let mut rt = world.non_send_resource_mut::<Runtime>();
-    rt.lock = None;
-
-    RUNTIME_CONTEXT.with(|runtime_cx| {
-        let mut cell = runtime_cx.borrow_mut();
-        let runtime_cx = cell.get_or_insert_with(|| RuntimeContext {
-            inner: Rc::new(RefCell::new(Inner {
-                world_ptr: ptr::null_mut(),
-                listeners: SlotMap::new(),
-                updates: Vec::new(),
-                commands: Rc::new(RefCell::new(CommandQueue::default())),
-            })),
-        });
+fn run_listeners(world: &mut World) {
+    let rt = Runtime::get(world);
 
-        runtime_cx.inner.borrow_mut().world_ptr = world as *mut World;
+    rt.cx.scope(world, || {
+        rt.apply_pending();
 
-        for f in runtime_cx.inner.borrow().listeners.values() {
-            f(world)
+        let listeners: Vec<_> = rt.cx.inner.borrow().listeners.values().cloned().collect();
+        for f in listeners {
+            rt.cx.with_world(|world| f(world));
         }
     });
+}
 
-    let rt = world.non_send_resource_mut::<Runtime>();
-    let mut composers = rt.composers.borrow_mut();
-    for rt_composer in composers.values_mut() {
-        rt_composer.guard = None;
-        rt_composer.composer.compose();
-    }
-    drop(composers);
+fn compose(world: &mut World) {
+    let rt = Runtime::get(world);
 
-    while let Ok(update) = rt.rx.try_recv() {
-        unsafe { update.apply() }
-    }
+    rt.cx.scope(world, || {
+        // Compositions may have been removed since the listeners ran.
+        rt.apply_pending();
 
-    {
-        world.increment_change_tick();
-        let rt_cx = RuntimeContext::current();
-        let mut rt = rt_cx.inner.borrow_mut();
-        for f in &mut rt.updates {
-            f(world);
+        let mut composers = rt.composers.borrow_mut();
+        for rt_composer in composers.values_mut() {
+            rt_composer.guard = None;
+        }
+
+        // Apply any updates queued since the last composition (e.g. from listeners or observers).
+        rt.apply_updates();
+
+        for rt_composer in composers.values_mut() {
+            let composer = &mut rt_composer.composer;
+            match &rt_composer.debug {
+                Some(debug) => debug::record(debug, || composer.compose()),
+                None => composer.compose(),
+            }
         }
+    });
+}
+
+fn apply_updates(world: &mut World) {
+    let rt = Runtime::get(world);
 
-        rt.updates.clear();
+    // Discard the updates of any compositions removed since they were composed.
+    rt.cx.scope(world, || rt.apply_pending());
+    rt.apply_updates();
 
-        rt.commands.borrow_mut().apply(world);
+    world.increment_change_tick();
+
+    let updates = mem::take(&mut rt.cx.inner.borrow_mut().updates);
+    for (_, f) in updates {
+        f(world);
     }
+}
+
+fn flush_commands(world: &mut World) {
+    let rt = Runtime::get(world);
+
+    let mut commands = mem::take(&mut *rt.cx.inner.borrow().commands.borrow_mut());
+    commands.apply(world);
 
-    let rt = &mut *world.non_send_resource_mut::<Runtime>();
-    let mut composers = rt.composers.borrow_mut();
-    for rt_composer in composers.values_mut() {
+    for rt_composer in rt.composers.borrow_mut().values_mut() {
         let guard = rt_composer.composer.lock();
+
+        // Safety: The guard borrows a lock behind an `Arc` owned by the composer,
+        // and it's dropped before the composer (see `RuntimeComposer`).
         let guard: RwLockWriteGuard<'static, ()> = unsafe { mem::transmute(guard) };
         rt_composer.guard = Some(guard);
     }
@@ -393,38 +735,349 @@ impl_system_param_fn!(T1, T2, T3, T4, T5, T6, T7, T8);
 /// `with_world` will be called on every frame with the latest query.
 ///
 /// Change detection is implemented as a traditional system parameter.
-pub fn use_world<'a, Marker, F>(cx: ScopeState<'a>, mut with_world: F)
+pub fn use_world<'a, Marker, F>(cx: ScopeState<'a>, with_world: F)
+where
+    F: SystemParamFunction<Marker, In = (), Out = ()> + 'a,
+{
+    use_world_with_priority(cx, 0, with_world)
+}
+
+/// Use a [`SystemParam`] from the ECS world, running before listeners with a lower `priority`.
+///
+/// `with_world` will be called on every frame with the latest query.
+///
+/// Listeners registered with [`use_world`] (and the other world hooks) have a priority of `0`.
+/// Listeners with the same priority run in the order they were registered,
+/// which is the order their scopes were first composed.
+/// If `priority` changes on re-composition, the listener runs after the existing listeners with its new priority.
+///
+/// ```no_run
+/// use actuate::prelude::*;
+/// use bevy::prelude::*;
+/// use bevy_mod_actuate::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// #[derive(Data)]
+/// struct ScoreKeeper;
+///
+/// impl Compose for ScoreKeeper {
+///     fn compose(cx: Scope<Self>) -> impl Compose {
+///         // Update the score before any other listener reads it.
+///         use_world_with_priority(&cx, 1, |mut score: ResMut<Score>| score.0 += 1);
+///     }
+/// }
+/// ```
+pub fn use_world_with_priority<'a, Marker, F>(cx: ScopeState<'a>, priority: i32, mut with_world: F)
 where
     F: SystemParamFunction<Marker, In = (), Out = ()> + 'a,
 {
     let system_state_cell = use_ref(cx, || RefCell::new(None));
 
+    use_world_listener(cx, priority, move |world| {
+        run_system_param_fn(system_state_cell, world, &mut with_world)
+    });
+}
+
+/// Use a [`SystemParam`] from the ECS world when a run `condition` is met.
+///
+/// `condition` is checked on every frame, and `with_world` is only called with the latest query
+/// when it returns `true`.
+/// Any Bevy run condition can be used (e.g. `resource_changed`, `in_state`, or `on_timer`).
+///
+/// The condition is created and initialized during the first composition.
+///
+/// ```no_run
+/// use actuate::prelude::{Mut, *};
+/// use bevy::prelude::*;
+/// use bevy_mod_actuate::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// #[derive(Data)]
+/// struct ScoreLabel;
+///
+/// impl Compose for ScoreLabel {
+///     fn compose(cx: Scope<Self>) -> impl Compose {
+///         let score = use_mut(&cx, || 0);
+///
+///         use_world_if(&cx, resource_changed::<Score>, move |res: Res<Score>| {
+///             Mut::set(score, res.0)
+///         });
+///
+///         spawn(Text::new(format!("Score: {}", *score)))
+///     }
+/// }
+/// ```
+pub fn use_world_if<'a, Marker, F, ConditionMarker, C>(
+    cx: ScopeState<'a>,
+    condition: C,
+    with_world: F,
+) where
+    C: Condition<ConditionMarker>,
+    F: SystemParamFunction<Marker, In = (), Out = ()> + 'a,
+{
+    use_world_if_with_priority(cx, 0, condition, with_world)
+}
+
+/// Use a [`SystemParam`] from the ECS world when a run `condition` is met,
+/// running before listeners with a lower `priority`.
+///
+/// See [`use_world_if`] and [`use_world_with_priority`] for more information.
+///
+/// ```no_run
+/// use actuate::prelude::*;
+/// use bevy::prelude::*;
+/// use bevy_mod_actuate::prelude::*;
+///
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// #[derive(Data)]
+/// struct ScoreKeeper;
+///
+/// impl Compose for ScoreKeeper {
+///     fn compose(cx: Scope<Self>) -> impl Compose {
+///         // Update the score before any other listener reads it, if it exists.
+///         use_world_if_with_priority(
+///             &cx,
+///             1,
+///             resource_exists::<Score>,
+///             |mut score: ResMut<Score>| score.0 += 1,
+///         );
+///     }
+/// }
+/// ```
+pub fn use_world_if_with_priority<'a, Marker, F, ConditionMarker, C>(
+    cx: ScopeState<'a>,
+    priority: i32,
+    condition: C,
+    mut with_world: F,
+) where
+    C: Condition<ConditionMarker>,
+    F: SystemParamFunction<Marker, In = (), Out = ()> + 'a,
+{
+    let system_state_cell = use_ref(cx, || RefCell::new(None));
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
+    let condition = use_ref(cx, || {
+        let mut system = IntoSystem::into_system(condition);
+        rt_cx.with_world(|world| system.initialize(world));
+        RefCell::new(system)
+    });
+
+    use_world_listener(cx, priority, move |world| {
+        if condition.borrow_mut().run((), world) {
+            run_system_param_fn(system_state_cell, world, &mut with_world)
+        }
+    });
+}
+
+/// Use the output of a [`SystemParam`] function from the ECS world.
+///
+/// `with_world` is called once during the first composition and then on every frame with the latest query.
+/// This scope is only re-composed when its output changes.
+///
+/// ```no_run
+/// use actuate::prelude::*;
+/// use bevy::prelude::*;
+/// use bevy_mod_actuate::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Enemy;
+///
+/// #[derive(Data)]
+/// struct EnemyCount;
+///
+/// impl Compose for EnemyCount {
+///     fn compose(cx: Scope<Self>) -> impl Compose {
+///         let count = use_world_memo(&cx, |query: Query<&Enemy>| query.iter().count());
+///
+///         spawn(Text::new(format!("Enemies: {}", *count)))
+///     }
+/// }
+/// ```
+pub fn use_world_memo<'a, Marker, F>(cx: ScopeState<'a>, mut with_world: F) -> Ref<'a, F::Out>
+where
+    F: SystemParamFunction<Marker, In = ()> + 'a,
+    F::Out: PartialEq + 'static,
+{
+    let system_state_cell = use_ref(cx, || RefCell::new(None));
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
+    let value = use_mut(cx, || {
+        rt_cx.with_world(|world| run_system_param_fn(system_state_cell, world, &mut with_world))
+    });
+
+    use_world_listener(cx, 0, move |world| {
+        let output = run_system_param_fn(system_state_cell, world, &mut with_world);
+        if output != *value {
+            Mut::set(value, output);
+        }
+    });
+
+    Mut::as_ref(value)
+}
+
+/// Cached [`SystemState`] of a world listener, with the world's entities to reserve entities from.
+type ListenerSystemState<P> = SystemState<(P, &'static Entities)>;
+
+/// Run `f` with its [`SystemParam`] fetched from a cached [`SystemState`].
+fn run_system_param_fn<Marker, F>(
+    system_state_cell: &RefCell<Option<ListenerSystemState<F::Param>>>,
+    world: &mut World,
+    f: &mut F,
+) -> F::Out
+where
+    F: SystemParamFunction<Marker, In = ()>,
+{
+    let mut system_state_cell = system_state_cell.borrow_mut();
+    let system_state = system_state_cell.get_or_insert_with(|| SystemState::new(world));
+    let (param, entities) = system_state.get_mut(world);
+    with_entities(entities, || f.run((), param))
+}
+
+/// Register `f` as a world listener that runs on every frame until this scope is dropped.
+fn use_world_listener<'a>(cx: ScopeState<'a>, priority: i32, mut f: impl FnMut(&mut World) + 'a) {
+    if let Ok(spawn_cx) = use_context::<SpawnContext>(cx) {
+        if let Some(debug) = &spawn_cx.debug {
+            debug::use_debug_listener(cx, debug, spawn_cx.parent_entity);
+        }
+    }
+
+    // Listeners run outside of composition, so they must enter this scope's runtime to queue updates.
+    let composer_rt = use_ref(cx, composer::Runtime::current);
+
     let f: Rc<dyn Fn(&'static mut World)> = use_callback(cx, move |world: &'static mut World| {
-        let mut system_state_cell = system_state_cell.borrow_mut();
-        let system_state =
-            system_state_cell.get_or_insert_with(|| SystemState::<F::Param>::new(world));
-        let query = system_state.get_mut(world);
-        with_world.run((), query)
+        composer_rt.enter();
+        f(world)
     })
     .clone();
 
-    let key = *use_ref(cx, || {
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
+    let key = use_ref(cx, || {
+        // Safety: The listener borrows this scope's state, so it's removed in `use_drop` before that state is dropped.
+        // Listeners only run in `run_listeners`, which never drops scopes while they're running.
         let f: Rc<dyn Fn(&mut World)> = unsafe { mem::transmute(f) };
 
-        RuntimeContext::current()
-            .inner
-            .borrow_mut()
-            .listeners
-            .insert(f)
+        let mut inner = rt_cx.inner.borrow_mut();
+        let key = (Reverse(priority), inner.next_listener_order);
+        inner.next_listener_order += 1;
+        inner.listeners.insert(key, f);
+        Cell::new(key)
     });
 
+    // Move the listener after the existing listeners of its new priority.
+    if key.get().0 != Reverse(priority) {
+        let mut inner = rt_cx.inner.borrow_mut();
+        let new_key = (Reverse(priority), inner.next_listener_order);
+        inner.next_listener_order += 1;
+        if let Some(f) = inner.listeners.remove(&key.get()) {
+            inner.listeners.insert(new_key, f);
+        }
+        key.set(new_key);
+    }
+
     use_drop(cx, move || {
-        RuntimeContext::current()
-            .inner
-            .borrow_mut()
-            .listeners
-            .remove(key);
+        rt_cx.inner.borrow_mut().listeners.remove(&key.get());
+    });
+}
+
+/// Use a snapshot of the results of a [`Query`].
+///
+/// `map` is called to create an owned snapshot of each item.
+/// This scope is only re-composed when an entity matching the query is added or removed,
+/// or when any component the query reads is changed.
+///
+/// ```no_run
+/// use actuate::prelude::*;
+/// use bevy::prelude::*;
+/// use bevy_mod_actuate::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Health(u32);
+///
+/// #[derive(Data)]
+/// struct HealthList;
+///
+/// impl Compose for HealthList {
+///     fn compose(cx: Scope<Self>) -> impl Compose {
+///         let healths = use_query::<&Health, (), _>(&cx, |health| health.0);
+///
+///         spawn(Text::new(format!("{:?}", *healths)))
+///     }
+/// }
+/// ```
+pub fn use_query<'a, D, F, T>(
+    cx: ScopeState<'a>,
+    map: impl Fn(QueryItem<'_, D>) -> T + 'a,
+) -> Ref<'a, Vec<T>>
+where
+    D: ReadOnlyQueryData + 'static,
+    F: QueryFilter + 'static,
+    T: 'static,
+{
+    let entities = use_ref(cx, || RefCell::new(Vec::new()));
+    let component_ids = use_ref(cx, || RefCell::new(None));
+
+    // Query the items if they changed since the last run.
+    let mut query_changed =
+        move |query: Query<(Entity, D), F>, world: &World, tick: SystemChangeTick| {
+            let mut component_ids = component_ids.borrow_mut();
+            let component_ids = component_ids.get_or_insert_with(|| {
+                let mut access = FilteredAccess::default();
+                if let Some(state) = D::get_state(world.components()) {
+                    D::update_component_access(&state, &mut access);
+                }
+
+                world
+                    .components()
+                    .iter()
+                    .map(|info| info.id())
+                    .filter(|id| access.access().has_component_read(*id))
+                    .collect::<Vec<_>>()
+            });
+
+            let new_entities: Vec<_> = query.iter().map(|(entity, _)| entity).collect();
+            let mut entities = entities.borrow_mut();
+
+            let is_changed = *entities != new_entities
+                || new_entities.iter().any(|entity| {
+                    let entity_ref = world.entity(*entity);
+                    component_ids.iter().any(|id| {
+                        entity_ref
+                            .get_change_ticks_by_id(*id)
+                            .is_some_and(|ticks| ticks.is_changed(tick.last_run(), tick.this_run()))
+                    })
+                });
+
+            if is_changed {
+                *entities = new_entities;
+                Some(query.iter().map(|(_, item)| map(item)).collect::<Vec<_>>())
+            } else {
+                None
+            }
+        };
+
+    // Query the initial items while composing, so the first run of the listener only sees later changes.
+    let system_state_cell = use_ref(cx, || RefCell::new(None));
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+    let items = use_mut(cx, || {
+        rt_cx
+            .with_world(|world| run_system_param_fn(system_state_cell, world, &mut query_changed))
+            .unwrap_or_default()
+    });
+
+    use_world_listener(cx, 0, move |world| {
+        if let Some(new_items) = run_system_param_fn(system_state_cell, world, &mut query_changed) {
+            Mut::set(items, new_items);
+        }
     });
+
+    Mut::as_ref(items)
 }
 
 /// A function that takes a [`SystemParam`] as input.
@@ -467,94 +1120,875 @@ impl_system_param_fn_once!(T1, T2, T3, T4, T5, T6, T7, T8);
 /// Use a [`SystemParam`] from the ECS world.
 ///
 /// `with_world` will be called once during the first composition.
-pub fn use_world_once<Marker, F>(cx: ScopeState, with_world: F) -> &F::Output
+pub fn use_world_once<Marker, F>(cx: ScopeState<'_>, with_world: F) -> &F::Output
 where
     F: SystemParamFunctionOnce<Marker>,
 {
     use_ref(cx, || {
-        let world = unsafe { RuntimeContext::current().world_mut() };
-        let mut param = SystemState::<F::Param>::new(world);
-        let item = param.get_mut(world);
-        with_world.run(item)
+        RuntimeContext::current().with_world(|world| {
+            let mut param = SystemState::<F::Param>::new(world);
+            let item = param.get_mut(world);
+            with_world.run(item)
+        })
     })
 }
 
-/// Hook for [`use_commands`].
-pub struct UseCommands {
-    commands: Rc<RefCell<CommandQueue>>,
+/// Use a [`Resource`] from the ECS world, or `None` if it doesn't exist.
+///
+/// This scope is only re-composed when the resource is changed, inserted, or removed.
+pub fn use_resource<R>(cx: ScopeState<'_>) -> Ref<'_, Option<R>>
+where
+    R: Resource + Clone,
+{
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+    let last_changed = use_ref(cx, || Cell::new(None));
+
+    let value = use_mut(cx, || {
+        rt_cx.with_world(|world| {
+            let resource = world.get_resource_ref::<R>();
+            last_changed.set(resource.as_ref().map(|resource| resource.last_changed()));
+            resource.map(|resource| R::clone(&resource))
+        })
+    });
+
+    use_world(cx, move |resource: Option<Res<R>>| {
+        let changed = resource.as_ref().map(|resource| resource.last_changed());
+        if changed != last_changed.get() {
+            last_changed.set(changed);
+            Mut::set(value, resource.map(|resource| R::clone(&resource)));
+        }
+    });
+
+    Mut::as_ref(value)
 }
 
-impl UseCommands {
-    /// Push a [`Command`] to the command queue.
-    pub fn push<C>(&mut self, command: C)
-    where
-        C: Command,
-    {
-        self.commands.borrow_mut().push(command);
+/// Hook for [`use_resource_mut`].
+pub struct UseResourceMut<'a, R> {
+    value: Ref<'a, Option<R>>,
+    rt_cx: &'a RuntimeContext,
+}
+
+impl<R> Clone for UseResourceMut<'_, R> {
+    fn clone(&self) -> Self {
+        *self
     }
 }
 
-/// Use access to the current [`Command`] queue.
-pub fn use_commands(cx: ScopeState) -> &UseCommands {
-    use_ref(cx, || {
-        let commands = RuntimeContext::current().inner.borrow().commands.clone();
-        UseCommands { commands }
-    })
+impl<R> Copy for UseResourceMut<'_, R> {}
+
+impl<R> Deref for UseResourceMut<'_, R> {
+    type Target = Option<R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
 }
 
-struct SpawnContext {
-    parent_entity: Entity,
+impl<R: Resource> UseResourceMut<'_, R> {
+    /// Queue an update to set the resource to `value`, inserting it if it doesn't exist.
+    ///
+    /// The resource will be updated at the end of the next composition.
+    pub fn set(self, value: R) {
+        self.rt_cx
+            .queue_update(move |world| world.insert_resource(value));
+    }
+
+    /// Queue an update to the resource, if it exists.
+    ///
+    /// The resource will be updated at the end of the next composition.
+    pub fn update(self, f: impl FnOnce(&mut R) + 'static) {
+        self.rt_cx.queue_update(move |world| {
+            if let Some(mut resource) = world.get_resource_mut::<R>() {
+                f(&mut resource);
+            }
+        });
+    }
 }
 
-/// Use a spawned bundle.
+/// Use a mutable [`Resource`] from the ECS world.
 ///
-/// `make_bundle` is called once to create the bundle.
-pub fn use_bundle<B: Bundle>(cx: ScopeState, make_bundle: impl FnOnce() -> B) -> Entity {
-    use_bundle_inner(cx, |world, cell| {
-        let bundle = make_bundle();
-        if let Some(entity) = cell {
-            world.entity_mut(*entity).insert(bundle);
-        } else {
-            *cell = Some(world.spawn(bundle).id());
-        }
-    })
+/// This scope is only re-composed when the resource is changed, inserted, or removed.
+/// See [`use_resource`] for more.
+pub fn use_resource_mut<R>(cx: ScopeState<'_>) -> UseResourceMut<'_, R>
+where
+    R: Resource + Clone,
+{
+    let value = use_resource(cx);
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+    UseResourceMut { value, rt_cx }
 }
 
-type SpawnFn = Arc<dyn Fn(&mut World, &mut Option<Entity>)>;
-
-/// Create a [`Spawn`] composable that spawns the provided `bundle` when composed.
+/// Use a [`Component`] of `entity`, or `None` if it doesn't exist.
 ///
-/// On re-composition, the spawned entity is updated to the latest provided value.
-pub fn spawn<'a, B>(bundle: B) -> Spawn<'a, ()>
+/// This scope is only re-composed when the component is changed, inserted, or removed
+/// (including when `entity` is despawned).
+pub fn use_component<T>(cx: ScopeState<'_>, entity: Entity) -> Ref<'_, Option<T>>
 where
-    B: Bundle + Clone,
+    T: Component + Clone,
 {
-    spawn_with(bundle, ())
+    let (value, _) = use_component_inner(cx, entity);
+    Mut::as_ref(value)
+}
+
+/// Last seen change of a component, used to detect external changes.
+type LastChanged = Rc<Cell<(Entity, Option<Tick>)>>;
+
+fn use_component_inner<T>(cx: ScopeState<'_>, entity: Entity) -> (Mut<'_, Option<T>>, &LastChanged)
+where
+    T: Component + Clone,
+{
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+    let last: &LastChanged = use_ref(cx, || Rc::new(Cell::new((entity, None))));
+
+    let value = use_mut(cx, || {
+        rt_cx.with_world(|world| {
+            let component = world
+                .get_entity(entity)
+                .ok()
+                .and_then(|entity_ref| entity_ref.get_ref::<T>());
+            last.set((
+                entity,
+                component.as_ref().map(|component| component.last_changed()),
+            ));
+            component.map(|component| T::clone(&component))
+        })
+    });
+
+    use_world(cx, move |query: Query<bevy::prelude::Ref<T>>| {
+        let component = query.get(entity).ok();
+        let changed = (
+            entity,
+            component.as_ref().map(|component| component.last_changed()),
+        );
+        if changed != last.get() {
+            last.set(changed);
+            Mut::set(value, component.map(|component| T::clone(&component)));
+        }
+    });
+
+    (value, last)
+}
+
+/// Hook for [`use_bound_component`].
+pub struct UseBoundComponent<'a, T> {
+    entity: Entity,
+    value: Mut<'a, Option<T>>,
+    last: &'a LastChanged,
+    /// Latest value written by [`UseBoundComponent::update`], to read back into the bound value.
+    written: &'a Rc<RefCell<Option<T>>>,
+    rt_cx: &'a RuntimeContext,
+}
+
+impl<T> Clone for UseBoundComponent<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for UseBoundComponent<'_, T> {}
+
+impl<T> Deref for UseBoundComponent<'_, T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: Component + Clone> UseBoundComponent<'_, T> {
+    /// Set the component to `value`, inserting it if it doesn't exist.
+    ///
+    /// The bound value is updated immediately, and the component is updated at the end of the next composition.
+    pub fn set(self, value: T) {
+        Mut::set(self.value, Some(value.clone()));
+
+        let entity = self.entity;
+        let last = self.last.clone();
+        self.rt_cx.queue_update(move |world| {
+            let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+                return;
+            };
+
+            if let Some(mut component) = entity_mut.get_mut::<T>() {
+                *component = value;
+            } else {
+                entity_mut.insert(value);
+            }
+
+            // Ignore this change when it's read back into the bound value.
+            let changed = entity_mut
+                .get_ref::<T>()
+                .map(|component| component.last_changed());
+            last.set((entity, changed));
+        });
+    }
+
+    /// Update the component, if it exists.
+    ///
+    /// `f` is applied to the component at the end of the next composition,
+    /// and the result is read back into the bound value on the following frame.
+    pub fn update(self, f: impl FnOnce(&mut T) + 'static) {
+        let entity = self.entity;
+        let last = self.last.clone();
+        let written = self.written.clone();
+        self.rt_cx.queue_update(move |world| {
+            let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+                return;
+            };
+
+            let Some(mut component) = entity_mut.get_mut::<T>() else {
+                return;
+            };
+            f(&mut component);
+
+            // Read the result back as a write, rather than an external change.
+            last.set((entity, Some(component.last_changed())));
+            *written.borrow_mut() = Some(T::clone(&component));
+        });
+    }
+}
+
+/// Use a two-way binding to a [`Component`] of `entity`.
+///
+/// Changes to the component from the ECS world are read back into the bound value,
+/// re-composing this scope (see [`use_component`] for more).
+/// Writes through the returned handle are applied to the component at the end of the next composition,
+/// without being read back as an external change.
+pub fn use_bound_component<T>(cx: ScopeState<'_>, entity: Entity) -> UseBoundComponent<'_, T>
+where
+    T: Component + Clone,
+{
+    let (value, last) = use_component_inner(cx, entity);
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
+    let written = use_ref(cx, Rc::<RefCell<Option<T>>>::default);
+    use_world(cx, move || {
+        if let Some(component) = written.borrow_mut().take() {
+            Mut::set(value, Some(component));
+        }
+    });
+
+    UseBoundComponent {
+        entity,
+        value,
+        last,
+        written,
+        rt_cx,
+    }
+}
+
+/// Use the [`Event`]s of type `E`, calling `f` for each new event.
+///
+/// Events sent before this hook is first composed are skipped.
+pub fn use_events<'a, E>(cx: ScopeState<'a>, mut f: impl FnMut(&E) + 'a)
+where
+    E: Event,
+{
+    let cursor = use_ref(cx, || {
+        let cursor = RuntimeContext::current().with_world(|world| {
+            world
+                .get_resource::<Events<E>>()
+                .map(|events| events.get_cursor_current())
+        });
+        RefCell::new(cursor.unwrap_or_default())
+    });
+
+    use_world(cx, move |events: Option<Res<Events<E>>>| {
+        if let Some(events) = events {
+            for event in cursor.borrow_mut().read(&events) {
+                f(event);
+            }
+        }
+    });
+}
+
+/// Hook for [`use_event_writer`].
+pub struct UseEventWriter<'a, E> {
+    rt_cx: &'a RuntimeContext,
+    _marker: PhantomData<fn(E)>,
+}
+
+impl<E> Clone for UseEventWriter<'_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for UseEventWriter<'_, E> {}
+
+impl<E: Event> UseEventWriter<'_, E> {
+    /// Send an event.
+    ///
+    /// The event will be sent when the runtime's command queue is next flushed.
+    pub fn send(self, event: E) {
+        self.rt_cx
+            .inner
+            .borrow()
+            .commands
+            .borrow_mut()
+            .push(move |world: &mut World| {
+                world.send_event(event);
+            });
+    }
+}
+
+/// Use a writer for [`Event`]s of type `E`.
+pub fn use_event_writer<E>(cx: ScopeState<'_>) -> UseEventWriter<'_, E>
+where
+    E: Event,
+{
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
+    UseEventWriter {
+        rt_cx,
+        _marker: PhantomData,
+    }
+}
+
+/// Hook for [`use_bevy_state`].
+pub struct UseBevyState<'a, S> {
+    value: Ref<'a, Option<S>>,
+    rt_cx: &'a RuntimeContext,
+}
+
+impl<S> Clone for UseBevyState<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for UseBevyState<'_, S> {}
+
+impl<S> Deref for UseBevyState<'_, S> {
+    type Target = Option<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<S: FreelyMutableState> UseBevyState<'_, S> {
+    /// Queue a transition to `state` with [`NextState`].
+    ///
+    /// The transition will be queued when the runtime's command queue is next flushed.
+    pub fn set(self, state: S) {
+        self.rt_cx
+            .inner
+            .borrow()
+            .commands
+            .borrow_mut()
+            .push(move |world: &mut World| {
+                if let Some(mut next_state) = world.get_resource_mut::<NextState<S>>() {
+                    next_state.set(state);
+                }
+            });
+    }
+}
+
+/// Use the current value of the [`States`] `S`, or `None` if the state doesn't exist.
+///
+/// This scope is only re-composed when the state transitions to a different value.
+pub fn use_bevy_state<S>(cx: ScopeState<'_>) -> UseBevyState<'_, S>
+where
+    S: States,
+{
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
+    let value = use_mut(cx, || {
+        rt_cx.with_world(|world| {
+            world
+                .get_resource::<State<S>>()
+                .map(|state| state.get().clone())
+        })
+    });
+
+    use_world(cx, move |state: Option<Res<State<S>>>| {
+        let state = state.map(|state| state.get().clone());
+        if state != *value {
+            Mut::set(value, state);
+        }
+    });
+
+    UseBevyState {
+        value: Mut::as_ref(value),
+        rt_cx,
+    }
+}
+
+/// Create a [`StateSwitch`] composable that composes the content for the current value of the [`States`] `S`.
+///
+/// When the state transitions, the previous content is dropped (running its [`use_drop`] hooks)
+/// and the content for the new state is composed from scratch.
+pub fn match_state<'a, S, C>(f: impl Fn(&S) -> C + 'a) -> StateSwitch<'a, S, C>
+where
+    S: States,
+    C: Compose,
+{
+    StateSwitch { f: Box::new(f) }
+}
+
+/// State switch composable.
+///
+/// See [`match_state`] for more information.
+#[must_use = "Composables do nothing unless composed with `actuate::run` or returned from other composables"]
+pub struct StateSwitch<'a, S, C> {
+    f: Box<dyn Fn(&S) -> C + 'a>,
+}
+
+unsafe impl<S, C: Data> Data for StateSwitch<'_, S, C> {}
+
+impl<S, C> Compose for StateSwitch<'_, S, C>
+where
+    S: States,
+    C: Compose,
+{
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let state = use_bevy_state::<S>(&cx);
+
+        // The content alternates between two slots, so a new state always composes into a new scope.
+        let mounted = use_ref(&cx, || RefCell::new((None, false)));
+        let mut mounted = mounted.borrow_mut();
+        if mounted.0 != *state {
+            *mounted = ((*state).clone(), !mounted.1);
+        }
+
+        let content = state.as_ref().map(|state| (cx.me().f)(state));
+        if mounted.1 {
+            (None, content)
+        } else {
+            (content, None)
+        }
+    }
+}
+
+/// Hook for [`use_commands`].
+pub struct UseCommands {
+    commands: Rc<RefCell<CommandQueue>>,
+    rt_cx: RuntimeContext,
+}
+
+impl UseCommands {
+    /// Push a [`Command`] to the command queue.
+    pub fn push<C>(&self, command: C)
+    where
+        C: Command,
+    {
+        self.commands.borrow_mut().push(command);
+    }
+
+    /// Spawn a new entity with the provided `bundle`, returning its [`UseEntityCommands`].
+    ///
+    /// The entity is spawned when the command queue is next flushed.
+    /// It's reserved immediately from composition, world listeners, observers of a [`Spawn`],
+    /// and [`UseSystem`] continuations, so its [`UseEntityCommands::id`] is known.
+    /// Elsewhere (e.g. in [`Spawn::on_spawn`] and [`Spawn::on_update`], which borrow the world),
+    /// its id is only known once the command queue is flushed.
+    pub fn spawn<B: Bundle>(&self, bundle: B) -> UseEntityCommands<'_> {
+        let entity = match self.rt_cx.try_reserve_entity() {
+            Some(entity) => CommandsEntity::Id(entity),
+            None => {
+                let cell = Arc::new(OnceLock::new());
+                let spawned = cell.clone();
+                self.push(move |world: &mut World| {
+                    let _ = spawned.set(world.spawn_empty().id());
+                });
+                CommandsEntity::Spawned(cell)
+            }
+        };
+
+        let mut entity_commands = UseEntityCommands {
+            commands: self,
+            entity,
+        };
+        entity_commands.insert(bundle);
+        entity_commands
+    }
+
+    /// Get a [`UseEntityCommands`] for the provided `entity`.
+    pub fn entity(&self, entity: Entity) -> UseEntityCommands<'_> {
+        UseEntityCommands {
+            commands: self,
+            entity: CommandsEntity::Id(entity),
+        }
+    }
+
+    /// Trigger an [`Event`].
+    pub fn trigger(&self, event: impl Event) {
+        self.push(move |world: &mut World| world.trigger(event));
+    }
+
+    /// Trigger an [`Event`] for the provided `targets`.
+    pub fn trigger_targets(
+        &self,
+        event: impl Event,
+        targets: impl TriggerTargets + Send + Sync + 'static,
+    ) {
+        self.push(move |world: &mut World| world.trigger_targets(event, targets));
+    }
+
+    /// Run a registered system by its [`SystemId`].
+    pub fn run_system(&self, id: SystemId) {
+        self.run_system_with_input(id, ());
+    }
+
+    /// Run a registered system by its [`SystemId`], providing an input value.
+    pub fn run_system_with_input<I>(&self, id: SystemId<I>, input: I::Inner<'static>)
+    where
+        I: SystemInput<Inner<'static>: Send> + 'static,
+    {
+        self.push(move |world: &mut World| {
+            if let Err(error) = world.run_system_with_input(id, input) {
+                bevy::log::warn!("{error}");
+            }
+        });
+    }
+}
+
+/// Commands for an entity, created with [`UseCommands::entity`].
+///
+/// Commands for an entity that doesn't exist when they're applied are ignored.
+pub struct UseEntityCommands<'a> {
+    commands: &'a UseCommands,
+    entity: CommandsEntity,
+}
+
+/// Entity of a [`UseEntityCommands`].
+#[derive(Clone)]
+enum CommandsEntity {
+    Id(Entity),
+    /// Entity spawned by a command, set once that command is applied.
+    Spawned(Arc<OnceLock<Entity>>),
+}
+
+impl CommandsEntity {
+    fn get(&self) -> Option<Entity> {
+        match self {
+            CommandsEntity::Id(entity) => Some(*entity),
+            CommandsEntity::Spawned(cell) => cell.get().copied(),
+        }
+    }
+}
+
+impl UseEntityCommands<'_> {
+    /// Get the entity of these commands.
+    ///
+    /// Returns `None` if the entity is spawned by a command that hasn't been applied yet (see [`UseCommands::spawn`]).
+    pub fn id(&self) -> Option<Entity> {
+        self.entity.get()
+    }
+
+    /// Insert a [`Bundle`] into this entity.
+    pub fn insert(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.with_entity(move |mut entity| {
+            entity.insert(bundle);
+        })
+    }
+
+    /// Remove a [`Bundle`] from this entity.
+    pub fn remove<B: Bundle>(&mut self) -> &mut Self {
+        self.with_entity(|mut entity| {
+            entity.remove::<B>();
+        })
+    }
+
+    /// Despawn this entity.
+    pub fn despawn(&mut self) {
+        self.with_entity(|entity| entity.despawn());
+    }
+
+    /// Despawn this entity and its children.
+    pub fn despawn_recursive(&mut self) {
+        self.with_entity(|entity| entity.despawn_recursive());
+    }
+
+    fn with_entity(&mut self, f: impl FnOnce(EntityWorldMut) + Send + 'static) -> &mut Self {
+        let entity = self.entity.clone();
+        self.commands.push(move |world: &mut World| {
+            if let Some(entity_mut) = entity
+                .get()
+                .and_then(|entity| world.get_entity_mut(entity).ok())
+            {
+                f(entity_mut);
+            }
+        });
+        self
+    }
+}
+
+/// Use access to the current [`Command`] queue.
+pub fn use_commands(cx: ScopeState<'_>) -> &UseCommands {
+    use_ref(cx, || {
+        let rt_cx = RuntimeContext::current();
+        let commands = rt_cx.inner.borrow().commands.clone();
+        UseCommands { commands, rt_cx }
+    })
+}
+
+/// Hook for [`use_system`].
+pub struct UseSystem<'a, I: SystemInput = (), O = ()> {
+    id: SystemId<I, O>,
+    rt_cx: &'a RuntimeContext,
+    composer_rt: &'a composer::Runtime,
+    composition: Option<Entity>,
+}
+
+impl<I: SystemInput, O> Clone for UseSystem<'_, I, O> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: SystemInput, O> Copy for UseSystem<'_, I, O> {}
+
+impl<'a, I, O> UseSystem<'a, I, O>
+where
+    I: SystemInput + 'static,
+    O: 'static,
+{
+    /// Get the [`SystemId`] of the registered system.
+    pub fn id(&self) -> SystemId<I, O> {
+        self.id
+    }
+
+    /// Queue a run of the system with the provided `input`.
+    pub fn run_with(self, input: I::Inner<'static>) {
+        self.run_with_then(input, |_| {});
+    }
+
+    /// Queue a run of the system with the provided `input`, then call `f` with its output.
+    ///
+    /// `f` runs from within this scope's runtime, so it can update [`Mut`] values.
+    pub fn run_with_then(self, input: I::Inner<'static>, f: impl FnOnce(O) + 'a) {
+        let id = self.id;
+        let composer_rt = self.composer_rt;
+
+        let f: Box<dyn FnOnce(&mut World) + 'a> =
+            Box::new(move |world| match world.run_system_with_input(id, input) {
+                Ok(output) => {
+                    composer_rt.enter();
+                    with_entities(world.entities(), || f(output))
+                }
+                Err(error) => bevy::log::warn!("{error}"),
+            });
+        // Safety: `f` borrows this scope's state, so it's discarded in `Runtime::apply_pending`
+        // before the state of its composition is dropped (see below).
+        let f: UpdateFn = unsafe { mem::transmute(f) };
+
+        // Tag this update with its composition, so it's discarded before the composition's state is dropped.
+        self.rt_cx
+            .inner
+            .borrow_mut()
+            .updates
+            .push((self.composition, f));
+    }
+}
+
+impl<'a, O: 'static> UseSystem<'a, (), O> {
+    /// Queue a run of the system.
+    pub fn run(self) {
+        self.run_with(());
+    }
+
+    /// Queue a run of the system, then call `f` with its output.
+    ///
+    /// `f` runs from within this scope's runtime, so it can update [`Mut`] values.
+    pub fn run_then(self, f: impl FnOnce(O) + 'a) {
+        self.run_with_then((), f);
+    }
+}
+
+/// Use a one-shot system registered with [`World::register_system`].
+///
+/// The system is registered once, and removed with [`World::remove_system`] when this scope is dropped.
+pub fn use_system<I, O, Marker>(
+    cx: ScopeState<'_>,
+    system: impl IntoSystem<I, O, Marker> + 'static,
+) -> UseSystem<'_, I, O>
+where
+    I: SystemInput + 'static,
+    O: 'static,
+{
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+    let composer_rt = use_ref(cx, composer::Runtime::current);
+    let composition = use_context::<CompositionContext>(cx)
+        .ok()
+        .map(|composition_cx| composition_cx.entity);
+
+    let id = *use_ref(cx, || {
+        rt_cx.with_world(|world| world.register_system(system))
+    });
+
+    use_drop(cx, move || {
+        // The world is unavailable if it's being dropped along with the runtime.
+        rt_cx.try_with_world(|world| {
+            let _ = world.remove_system(id);
+        });
+    });
+
+    UseSystem {
+        id,
+        rt_cx,
+        composer_rt,
+        composition,
+    }
+}
+
+struct SpawnContext {
+    parent_entity: Entity,
+
+    /// Order of the entities spawned as children of `parent_entity`.
+    child_order: Rc<ChildOrder>,
+
+    /// Root entities spawned by a keyed list item, in composition order (see [`spawn_keyed_list`]).
+    keyed_entities: Option<Rc<RefCell<Vec<Entity>>>>,
+
+    /// Debug context of the content, if the [`DebugPlugin`](debug::DebugPlugin) has been added.
+    debug: Option<DebugContext>,
+}
+
+/// Use a spawned bundle.
+///
+/// `make_bundle` is called once to create the bundle.
+pub fn use_bundle<B: Bundle>(cx: ScopeState, make_bundle: impl FnOnce() -> B) -> Entity {
+    use_bundle_inner(
+        cx,
+        |world, cell| {
+            let bundle = make_bundle();
+            if let Some(entity) = cell {
+                world.entity_mut(*entity).insert(bundle);
+            } else {
+                *cell = Some(world.spawn(bundle).id());
+            }
+        },
+        despawn_entity,
+    )
+}
+
+/// Spawn or update a bundle, replacing the component IDs of the last bundle with those of the new one.
+type SpawnFn = Arc<dyn Fn(&mut World, &mut Option<Entity>, &mut Vec<ComponentId>)>;
+
+/// Create a [`Spawn`] composable that spawns the provided `bundle` when composed.
+///
+/// On re-composition, the spawned entity is updated to the latest provided value.
+pub fn spawn<'a, B>(bundle: B) -> Spawn<'a, ()>
+where
+    B: Bundle + Clone,
+{
+    spawn_with(bundle, ())
+}
+
+/// Create a [`Spawn`] composable that spawns the provided `bundle` when composed, with some content as its children.
+///
+/// On re-composition, the spawned entity is updated to the latest provided value.
+/// Any components inserted by a previous bundle that are missing from the latest one
+/// (and aren't required by its components) are removed.
+pub fn spawn_with<'a, B, C>(bundle: B, content: C) -> Spawn<'a, C>
+where
+    B: Bundle + Clone,
+    C: Compose,
+{
+    Spawn {
+        spawn_fn: bundle_spawn_fn(bundle, |bundle, entity_mut| {
+            entity_mut.insert(bundle.clone());
+        }),
+        content,
+        target: None,
+        bundle_name: any::type_name::<B>(),
+        observer_fns: Vec::new(),
+        on_spawn_fns: Cell::default(),
+        on_update_fns: Vec::new(),
+    }
+}
+
+/// Create a [`Spawn`] composable that spawns the provided `bundle` when composed.
+///
+/// On re-composition, only the components that changed since the last composition are re-inserted.
+/// See [`DiffBundle`] for more information.
+pub fn spawn_diff<'a, B>(bundle: B) -> Spawn<'a, ()>
+where
+    B: DiffBundle,
+{
+    spawn_diff_with(bundle, ())
 }
 
 /// Create a [`Spawn`] composable that spawns the provided `bundle` when composed, with some content as its children.
 ///
-/// On re-composition, the spawned entity is updated to the latest provided value.
-pub fn spawn_with<'a, B, C>(bundle: B, content: C) -> Spawn<'a, C>
+/// On re-composition, only the components that changed since the last composition are re-inserted.
+/// See [`DiffBundle`] for more information.
+pub fn spawn_diff_with<'a, B, C>(bundle: B, content: C) -> Spawn<'a, C>
 where
-    B: Bundle + Clone,
+    B: DiffBundle,
     C: Compose,
 {
     Spawn {
-        spawn_fn: Arc::new(move |world, cell| {
-            if let Some(entity) = cell {
-                world.entity_mut(*entity).insert(bundle.clone());
-            } else {
-                *cell = Some(world.spawn(bundle.clone()).id())
-            }
-        }),
+        spawn_fn: bundle_spawn_fn(bundle, B::insert_diff),
         content,
         target: None,
+        bundle_name: any::type_name::<B>(),
         observer_fns: Vec::new(),
+        on_spawn_fns: Cell::default(),
+        on_update_fns: Vec::new(),
     }
 }
 
-type ObserverFn<'a> = Box<dyn Fn(&mut EntityWorldMut) + 'a>;
+/// Bundle that can be compared to an entity's components.
+///
+/// This is implemented for tuples of components that implement [`PartialEq`],
+/// so a single component can be diffed as `(component,)`.
+pub trait DiffBundle: Bundle + Clone {
+    /// Insert each component of this bundle that isn't equal to the entity's current value.
+    fn insert_diff(&self, entity: &mut EntityWorldMut);
+}
+
+macro_rules! impl_diff_bundle {
+    ($($t:ident),*) => {
+        impl<$($t: Component + Clone + PartialEq),*> DiffBundle for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn insert_diff(&self, entity: &mut EntityWorldMut) {
+                let ($($t,)*) = self;
+                $(
+                    if entity.get::<$t>() != Some($t) {
+                        entity.insert($t.clone());
+                    }
+                )*
+            }
+        }
+    };
+}
+
+impl_diff_bundle!(A);
+impl_diff_bundle!(A, B);
+impl_diff_bundle!(A, B, C);
+impl_diff_bundle!(A, B, C, D);
+impl_diff_bundle!(A, B, C, D, E);
+impl_diff_bundle!(A, B, C, D, E, F);
+impl_diff_bundle!(A, B, C, D, E, F, G);
+impl_diff_bundle!(A, B, C, D, E, F, G, H);
+impl_diff_bundle!(A, B, C, D, E, F, G, H, I);
+impl_diff_bundle!(A, B, C, D, E, F, G, H, I, J);
+impl_diff_bundle!(A, B, C, D, E, F, G, H, I, J, K);
+impl_diff_bundle!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+fn bundle_spawn_fn<B>(bundle: B, insert: impl Fn(&B, &mut EntityWorldMut) + 'static) -> SpawnFn
+where
+    B: Bundle + Clone,
+{
+    Arc::new(move |world, cell, component_ids| {
+        // Include required components, so they're kept as long as a component that requires them is.
+        let new_component_ids = world
+            .register_bundle::<B>()
+            .contributed_components()
+            .to_vec();
+
+        if let Some(entity) = cell {
+            insert(&bundle, &mut world.entity_mut(*entity));
+        } else {
+            *cell = Some(world.spawn(bundle.clone()).id())
+        }
+
+        *component_ids = new_component_ids;
+    })
+}
 
 /// Spawn composable with content.
 ///
@@ -564,9 +1998,16 @@ pub struct Spawn<'a, C> {
     spawn_fn: SpawnFn,
     content: C,
     target: Option<Entity>,
+    bundle_name: &'static str,
     observer_fns: Vec<ObserverFn<'a>>,
+    on_spawn_fns: Cell<Vec<OnSpawnFn<'a>>>,
+    on_update_fns: Vec<OnUpdateFn<'a>>,
 }
 
+type OnSpawnFn<'a> = Box<dyn FnOnce(EntityWorldMut) + 'a>;
+
+type OnUpdateFn<'a> = Box<dyn Fn(EntityWorldMut) + 'a>;
+
 impl<'a, C> Spawn<'a, C> {
     /// Get the target entity to spawn the composition into.
     ///
@@ -585,103 +2026,802 @@ impl<'a, C> Spawn<'a, C> {
     /// Set the target entity to spawn the composition into.
     ///
     /// If `None`, this will use the composition's parent (if any).
+    ///
+    /// The target entity isn't despawned when this composable is dropped.
+    /// Instead, the components and observers it added are removed,
+    /// while components the target already had are kept.
+    /// Likewise, only the components it added are removed when they're missing from a re-composed bundle.
     pub fn with_target(mut self, target: Entity) -> Self {
         self.target = Some(target);
         self
     }
 
+    /// Add a function to run once the entity is spawned.
+    pub fn on_spawn(mut self, f: impl FnOnce(EntityWorldMut) + 'a) -> Self {
+        self.on_spawn_fns.get_mut().push(Box::new(f));
+        self
+    }
+
+    /// Add a function to run each time this composable is composed, after the entity is updated.
+    pub fn on_update(mut self, f: impl Fn(EntityWorldMut) + 'a) -> Self {
+        self.on_update_fns.push(Box::new(f));
+        self
+    }
+
     /// Add an observer to the spawned entity.
-    pub fn observe<F, E, B, Marker>(mut self, observer: F) -> Self
+    ///
+    /// The observer's function is replaced each time this composable is re-composed,
+    /// so it always captures the latest values from its scope.
+    pub fn observe<F, E, B, Marker>(self, observer: F) -> Self
+    where
+        F: SystemParamFunction<Marker, In = Trigger<'static, E, B>, Out = ()> + 'a,
+        E: Event,
+        B: Bundle,
+    {
+        self.observe_inner(Box::new(|_| true), observer)
+    }
+
+    /// Add an observer to the spawned entity whose function is only replaced when `dependency` changes.
+    pub fn observe_with_deps<D, F, E, B, Marker>(self, dependency: D, observer: F) -> Self
     where
-        F: SystemParamFunction<Marker, In = Trigger<'static, E, B>, Out = ()> + Send + Sync + 'a,
+        D: Memoize,
+        F: SystemParamFunction<Marker, In = Trigger<'static, E, B>, Out = ()> + 'a,
+        E: Event,
+        B: Bundle,
+    {
+        let dependency_cell = Cell::new(Some(dependency.memoized()));
+        self.observe_inner(
+            Box::new(move |last| {
+                let Some(dependency) = dependency_cell.take() else {
+                    return false;
+                };
+
+                if last.as_ref().and_then(|last| last.downcast_ref()) == Some(&dependency) {
+                    return false;
+                }
+
+                *last = Some(Box::new(dependency));
+                true
+            }),
+            observer,
+        )
+    }
+
+    fn observe_inner<F, E, B, Marker>(
+        mut self,
+        is_changed: ObserverChangedFn<'a>,
+        observer: F,
+    ) -> Self
+    where
+        F: SystemParamFunction<Marker, In = Trigger<'static, E, B>, Out = ()> + 'a,
         E: Event,
         B: Bundle,
     {
         let cell = Cell::new(Some(observer));
-        self.observer_fns.push(Box::new(move |entity| {
-            let mut observer = cell.take().unwrap();
-
-            type SpawnObserveFn<'a, F, E, B, Marker> = Box<
-                dyn FnMut(
-                        Trigger<'_, E, B>,
-                        ParamSet<'_, '_, (<F as SystemParamFunction<Marker>>::Param,)>,
-                    ) + Send
-                    + Sync
-                    + 'a,
-            >;
-
-            let f: SpawnObserveFn<'a, F, E, B, Marker> = Box::new(move |trigger, mut params| {
-                let trigger: Trigger<'static, E, B> = unsafe { mem::transmute(trigger) };
-                observer.run(trigger, params.p0())
-            });
-            let f: SpawnObserveFn<'static, F, E, B, Marker> = unsafe { mem::transmute(f) };
+        let spawn = Box::new(
+            move |world: &mut World, entity, spawn_observer: &mut SpawnObserver| {
+                let Some(mut observer) = cell.take() else {
+                    return;
+                };
+
+                let f: SpawnObserveFn<'a, E, B, F::Param> = Box::new(move |trigger, mut params| {
+                    // Safety: The trigger only borrows the event for this call,
+                    // so observers can't hold onto it after they return.
+                    let trigger: Trigger<'static, E, B> = unsafe { mem::transmute(trigger) };
+                    observer.run(trigger, params.p0())
+                });
+                // Safety: `f` borrows this scope's state, so it's replaced on re-composition,
+                // and its observer is despawned before the state is dropped (or with the world, if that's dropped first).
+                let f: SpawnObserveFn<'static, E, B, F::Param> = unsafe { mem::transmute(f) };
+
+                // Swap the function of the existing observer, rather than spawning a new one,
+                // as Bevy doesn't remove despawned observers from the entity's `ObservedBy`.
+                let observer_cell = spawn_observer
+                    .entity
+                    .filter(|observer_entity| world.get_entity(*observer_entity).is_ok())
+                    .and(spawn_observer.cell.as_ref())
+                    .and_then(|cell| cell.downcast_ref::<SpawnObserveCell<E, B, F::Param>>());
+                if let Some(observer_cell) = observer_cell {
+                    *observer_cell.borrow_mut() = Some(f);
+                    return;
+                }
+
+                let observer_cell: SpawnObserveCell<E, B, F::Param> =
+                    Rc::new(RefCell::new(Some(f)));
+
+                // Observers run outside of composition, so they must enter this scope's runtime to queue updates.
+                let run_state =
+                    ThreadBound::new((observer_cell.clone(), composer::Runtime::current()));
+                let observer_entity = world
+                    .spawn(
+                        Observer::new(
+                            move |trigger: Trigger<E, B>,
+                                  entities: &Entities,
+                                  params: ParamSet<(F::Param,)>| {
+                                let (run_cell, composer_rt) = run_state.get();
+
+                                // Take the function out while it runs, in case it triggers this observer again.
+                                let Some(mut f) = run_cell.borrow_mut().take() else {
+                                    return;
+                                };
+                                composer_rt.enter();
+                                with_entities(entities, || f(trigger, params));
+                                run_cell.borrow_mut().get_or_insert(f);
+                            },
+                        )
+                        .with_entity(entity),
+                    )
+                    .id();
+
+                if let Some(last_entity) = spawn_observer.entity.replace(observer_entity) {
+                    world.try_despawn(last_entity);
+                }
+                spawn_observer.cell = Some(Box::new(observer_cell));
+            },
+        );
 
-            entity.observe(f);
-        }));
+        self.observer_fns.push(ObserverFn { is_changed, spawn });
         self
     }
 }
 
+type ObserverChangedFn<'a> = Box<dyn Fn(&mut Option<Box<dyn Any>>) -> bool + 'a>;
+
+type ObserverSpawnFn<'a> = Box<dyn Fn(&mut World, Entity, &mut SpawnObserver) + 'a>;
+
+/// Function run by an observer spawned by a [`Spawn`] composable.
+type SpawnObserveFn<'a, E, B, P> = Box<dyn FnMut(Trigger<'_, E, B>, ParamSet<'_, '_, (P,)>) + 'a>;
+
+/// Cell of the function run by an observer, replaced when the observer is rebound.
+type SpawnObserveCell<E, B, P> = Rc<RefCell<Option<SpawnObserveFn<'static, E, B, P>>>>;
+
+/// Value that can only be used on the thread it was created on.
+///
+/// Observers must be `Send + Sync`, so this lets them hold the (`!Send`) state of their scope,
+/// which is only ever accessed from the thread its composition runs on.
+struct ThreadBound<T> {
+    thread: ThreadId,
+    value: ManuallyDrop<T>,
+}
+
+impl<T> ThreadBound<T> {
+    fn new(value: T) -> Self {
+        Self {
+            thread: thread::current().id(),
+            value: ManuallyDrop::new(value),
+        }
+    }
+
+    /// Get the value.
+    ///
+    /// # Panics
+    /// Panics if called from a thread other than the one this value was created on.
+    fn get(&self) -> &T {
+        assert_eq!(
+            thread::current().id(),
+            self.thread,
+            "Observers of a composition must run on the thread it's composed on."
+        );
+        &self.value
+    }
+}
+
+impl<T> Drop for ThreadBound<T> {
+    fn drop(&mut self) {
+        // The value can't be dropped on another thread, so it's leaked instead.
+        if thread::current().id() == self.thread {
+            // Safety: The value is never used again.
+            unsafe { ManuallyDrop::drop(&mut self.value) }
+        }
+    }
+}
+
+// Safety: The value is only accessed (and dropped) on the thread it was created on, which is checked at runtime.
+unsafe impl<T> Send for ThreadBound<T> {}
+
+unsafe impl<T> Sync for ThreadBound<T> {}
+
+/// Observer of a [`Spawn`] composable.
+struct ObserverFn<'a> {
+    /// Returns `true` if the observer should be replaced, updating its last dependency.
+    is_changed: ObserverChangedFn<'a>,
+
+    /// Spawn or rebind this observer for an entity, if it wasn't already this composition.
+    spawn: ObserverSpawnFn<'a>,
+}
+
+/// State of an observer spawned by a [`Spawn`] composable.
+#[derive(Default)]
+struct SpawnObserver {
+    entity: Option<Entity>,
+    dependency: Option<Box<dyn Any>>,
+    /// Cell of the observer's function (see [`SpawnObserveCell`]).
+    cell: Option<Box<dyn Any>>,
+}
+
 unsafe impl<C: Data> Data for Spawn<'_, C> {}
 
 impl<C: Compose> Compose for Spawn<'_, C> {
     fn compose(cx: Scope<Self>) -> impl Compose {
+        // Spawning into a target (or another target) tears down what was spawned before,
+        // so a changed target always composes into a new scope.
+        let mounted = use_ref(&cx, || Cell::new((cx.me().target, false)));
+        let (target, slot) = mounted.get();
+        if target != cx.me().target {
+            mounted.set((cx.me().target, !slot));
+        }
+
+        let content = SpawnContent { spawn: cx.me() };
+        if mounted.get().1 {
+            (None, Some(content))
+        } else {
+            (Some(content), None)
+        }
+    }
+}
+
+/// Composable of the entity and content of a [`Spawn`], for as long as its target is unchanged.
+struct SpawnContent<'a, 'b, C> {
+    spawn: Ref<'a, Spawn<'b, C>>,
+}
+
+unsafe impl<C: Data> Data for SpawnContent<'_, '_, C> {}
+
+impl<C: Compose> Compose for SpawnContent<'_, '_, C> {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let spawn = cx.me().spawn;
         let spawn_cx = use_context::<SpawnContext>(&cx);
 
-        let is_initial = use_ref(&cx, || Cell::new(true));
-        let entity = use_bundle_inner(&cx, |world, entity| {
-            if let Some(target) = cx.me().target {
-                *entity = Some(target);
-            }
+        let observers = use_ref(&cx, || RefCell::new(Vec::<SpawnObserver>::new()));
+        let component_ids = use_ref(&cx, || RefCell::new(Vec::new()));
+        let is_target = *use_ref(&cx, || spawn.target.is_some());
+        let added_ids = use_ref(&cx, || RefCell::new(Vec::new()));
+        let entity = use_bundle_inner(
+            &cx,
+            |world, entity| {
+                let is_initial = entity.is_none();
+
+                if let Some(target) = spawn.target {
+                    *entity = Some(target);
+                }
 
-            (cx.me().spawn_fn)(world, entity);
+                // Components the target already had are owned by the user, so record which ones this adds.
+                let existing_ids: Option<Vec<ComponentId>> = spawn
+                    .target
+                    .map(|target| world.entity(target).archetype().components().collect());
+
+                let last_ids = component_ids.borrow().clone();
+                (spawn.spawn_fn)(world, entity, &mut component_ids.borrow_mut());
+                let entity = entity.unwrap();
+
+                // Remove the components of the last bundle that are missing from the new one,
+                // except for those a target already had.
+                let mut entity_mut = world.entity_mut(entity);
+                for id in last_ids {
+                    if !component_ids.borrow().contains(&id)
+                        && (!is_target || added_ids.borrow().contains(&id))
+                    {
+                        entity_mut.remove_by_id(id);
+                    }
+                }
 
-            if is_initial.get() {
-                let mut entity_mut = world.entity_mut(entity.unwrap());
-                for f in &cx.me().observer_fns {
-                    f(&mut entity_mut);
+                if let Some(existing_ids) = existing_ids {
+                    let mut added_ids = added_ids.borrow_mut();
+                    for id in component_ids.borrow().iter() {
+                        if !existing_ids.contains(id) && !added_ids.contains(id) {
+                            added_ids.push(*id);
+                        }
+                    }
                 }
 
-                is_initial.set(false);
-            }
-        });
+                let observer_fns = &spawn.observer_fns;
+                let mut observers = observers.borrow_mut();
+                let len = observers.len().max(observer_fns.len());
+                observers.resize_with(len, Default::default);
+
+                for (observer_fn, observer) in observer_fns.iter().zip(observers.iter_mut()) {
+                    if !(observer_fn.is_changed)(&mut observer.dependency) {
+                        continue;
+                    }
+
+                    (observer_fn.spawn)(world, entity, observer);
+                }
+
+                for observer in observers.drain(observer_fns.len()..) {
+                    if let Some(observer_entity) = observer.entity {
+                        world.try_despawn(observer_entity);
+                    }
+                }
+
+                if is_initial {
+                    for f in spawn.on_spawn_fns.take() {
+                        f(world.entity_mut(entity));
+                    }
+                }
+
+                for f in &spawn.on_update_fns {
+                    f(world.entity_mut(entity));
+                }
+            },
+            move |world, entity| {
+                if !is_target {
+                    despawn_entity(world, entity);
+                    return;
+                }
+
+                // Targets are owned by the user, so only remove what was added to them.
+                if let Ok(mut entity_mut) = world.get_entity_mut(entity) {
+                    let added_ids = added_ids.borrow();
+                    for id in component_ids.borrow().iter() {
+                        if added_ids.contains(id) {
+                            entity_mut.remove_by_id(*id);
+                        }
+                    }
+                }
+
+                for observer in observers.borrow().iter() {
+                    if let Some(observer_entity) = observer.entity {
+                        world.try_despawn(observer_entity);
+                    }
+                }
+            },
+        );
+
+        let child_order = use_ref(&cx, Rc::<ChildOrder>::default);
+
+        let debug = spawn_cx
+            .as_ref()
+            .ok()
+            .and_then(|spawn_cx| spawn_cx.debug.as_ref())
+            .map(|debug| {
+                debug::use_debug_spawn(
+                    &cx,
+                    debug,
+                    &spawn,
+                    entity,
+                    spawn_cx
+                        .as_ref()
+                        .ok()
+                        .filter(|_| spawn.target.is_none())
+                        .map(|spawn_cx| spawn_cx.parent_entity),
+                )
+            });
 
         use_provider(&cx, || {
-            if cx.me().target.is_none() {
-                if let Ok(parent_entity) = spawn_cx.map(|cx| cx.parent_entity) {
-                    let world = unsafe { RuntimeContext::current().world_mut() };
-                    world.entity_mut(parent_entity).add_child(entity);
+            if spawn.target.is_none() {
+                if let Ok(spawn_cx) = spawn_cx {
+                    RuntimeContext::current().with_world(|world| {
+                        world.entity_mut(spawn_cx.parent_entity).add_child(entity);
+                    });
+
+                    // `add_child` appends this entity, so move it to its position among its siblings.
+                    spawn_cx.child_order.queue_reorder(spawn_cx.parent_entity);
+
+                    if let Some(keyed_entities) = &spawn_cx.keyed_entities {
+                        keyed_entities.borrow_mut().push(entity);
+                    }
                 }
             }
 
             SpawnContext {
                 parent_entity: entity,
+                child_order: child_order.clone(),
+                keyed_entities: None,
+                debug: debug.clone(),
             }
         });
 
-        Ref::map(cx.me(), |me| &me.content)
+        let parent = if spawn.target.is_none() {
+            spawn_cx
+                .ok()
+                .map(|spawn_cx| (entity, spawn_cx.child_order.clone()))
+        } else {
+            None
+        };
+
+        (
+            ChildOrderProbe {
+                child_order: child_order.clone(),
+                parent,
+            },
+            debug.as_ref().map(DebugContext::enter_probe),
+            Ref::map(cx.me().spawn, |me| &me.content),
+            debug.as_ref().map(|_| debug::OwnerProbe::Exit),
+        )
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
+    }
+}
+
+/// Order of the entities spawned as children of an entity, in composition order.
+#[derive(Default)]
+struct ChildOrder {
+    entities: RefCell<Vec<Entity>>,
+    is_queued: Cell<bool>,
+}
+
+impl ChildOrder {
+    /// Queue a reorder of the children of `parent_entity` after this composition.
+    fn queue_reorder(self: &Rc<Self>, parent_entity: Entity) {
+        if self.is_queued.replace(true) {
+            return;
+        }
+
+        let child_order = self.clone();
+        RuntimeContext::current().queue_update(move |world| {
+            child_order.is_queued.set(false);
+            reorder_children(world, parent_entity, &child_order.entities.borrow());
+        });
+    }
+}
+
+fn reorder_children(world: &mut World, parent_entity: Entity, entities: &[Entity]) {
+    let entities: Vec<_> = entities
+        .iter()
+        .copied()
+        .filter(|entity| {
+            world
+                .get::<Parent>(*entity)
+                .is_some_and(|parent| parent.get() == parent_entity)
+        })
+        .collect();
+    let entity_set: HashSet<Entity> = entities.iter().copied().collect();
+
+    let Some(children) = world.get::<Children>(parent_entity) else {
+        return;
+    };
+
+    if children
+        .iter()
+        .filter(|entity| entity_set.contains(*entity))
+        .eq(entities.iter())
+    {
+        return;
+    }
+
+    // Composed children are grouped at the position of the first one.
+    let idx = children
+        .iter()
+        .position(|entity| entity_set.contains(entity))
+        .unwrap_or_default();
+
+    world
+        .entity_mut(parent_entity)
+        .insert_children(idx, &entities);
+}
+
+/// Composable to record the composition order of spawned entities.
+///
+/// This is re-composed every frame, so skipped siblings are still recorded in order.
+struct ChildOrderProbe {
+    /// Order of the children of this probe's entity, cleared before they're composed.
+    child_order: Rc<ChildOrder>,
+
+    /// This probe's entity and the order of its siblings.
+    parent: Option<(Entity, Rc<ChildOrder>)>,
+}
+
+unsafe impl Data for ChildOrderProbe {}
+
+impl Compose for ChildOrderProbe {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.set_changed();
+
+        cx.me().child_order.entities.borrow_mut().clear();
+
+        if let Some((entity, parent_order)) = &cx.me().parent {
+            parent_order.entities.borrow_mut().push(*entity);
+        }
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
+    }
+}
+
+/// Create a [`KeyedList`] composable that composes each item with `item_fn`, identified by its `key_fn`.
+///
+/// On re-composition, items with the same key keep their spawned entities and are moved
+/// to match the order of `items`, while items with removed keys are despawned.
+///
+/// Keys should be unique within `items`: if multiple items have the same key,
+/// only the first one is composed, and the rest are skipped with a warning.
+/// An item can spawn multiple root entities, which are kept together in the order they're composed.
+pub fn spawn_keyed_list<'a, T, K, C>(
+    items: impl IntoIterator<Item = T>,
+    key_fn: impl Fn(&T) -> K + 'a,
+    item_fn: impl Fn(&T) -> C + 'a,
+) -> KeyedList<'a, T, K, C>
+where
+    K: Hash + Eq + Clone + 'static,
+    C: Compose,
+{
+    KeyedList {
+        items: items.into_iter().collect(),
+        key_fn: Box::new(key_fn),
+        item_fn: Box::new(item_fn),
+    }
+}
+
+/// Keyed list composable.
+///
+/// See [`spawn_keyed_list`] for more information.
+#[must_use = "Composables do nothing unless composed with `actuate::run` or returned from other composables"]
+pub struct KeyedList<'a, T, K, C> {
+    items: Vec<T>,
+    key_fn: Box<dyn Fn(&T) -> K + 'a>,
+    item_fn: Box<dyn Fn(&T) -> C + 'a>,
+}
+
+unsafe impl<T, K, C: Data> Data for KeyedList<'_, T, K, C> {}
+
+impl<'a, T, K, C> Compose for KeyedList<'a, T, K, C>
+where
+    T: 'a,
+    K: Hash + Eq + Clone + 'static,
+    C: Compose + 'a,
+{
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let spawn_cx = use_context::<SpawnContext>(&cx);
+        let slots: &RefCell<Vec<KeyedSlot<K>>> = use_ref(&cx, || RefCell::new(Vec::new()));
+        let mut slots = slots.borrow_mut();
+
+        let mut indices: HashMap<K, usize> = HashMap::with_capacity(cx.me().items.len());
+        for (idx, item) in cx.me().items.iter().enumerate() {
+            let key = (cx.me().key_fn)(item);
+            if indices.contains_key(&key) {
+                bevy::log::warn!("Skipping item {idx} of keyed list with a duplicate key.");
+                continue;
+            }
+            indices.insert(key, idx);
+        }
+
+        // Slots freed in this composition aren't reused until the next one,
+        // so new keys never take over the entities of removed keys.
+        let free_slots: Vec<usize> = slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.0.key.borrow().is_none())
+            .map(|(idx, _)| idx)
+            .collect();
+
+        for slot in slots.iter() {
+            let mut key = slot.0.key.borrow_mut();
+            if let Some(idx) = key.as_ref().and_then(|key| indices.remove(key)) {
+                slot.0.index.set(idx);
+            } else {
+                *key = None;
+            }
+        }
+
+        let mut new_keys: Vec<_> = indices.into_iter().collect();
+        new_keys.sort_by_key(|(_, idx)| *idx);
+
+        let mut free_slots = free_slots.into_iter();
+        for (key, idx) in new_keys {
+            if let Some(slot_idx) = free_slots.next() {
+                let slot = &slots[slot_idx].0;
+                *slot.key.borrow_mut() = Some(key);
+                slot.index.set(idx);
+            } else {
+                slots.push(KeyedSlot(Rc::new(KeyedSlotState {
+                    key: RefCell::new(Some(key)),
+                    index: Cell::new(idx),
+                    entities: Rc::default(),
+                })));
+            }
+        }
+
+        while slots
+            .last()
+            .is_some_and(|slot| slot.0.key.borrow().is_none())
+        {
+            slots.pop();
+        }
+
+        (
+            (!slots.is_empty()).then(|| KeyedSlots {
+                slots: Rc::new(slots.clone()),
+                index: 0,
+                list: cx.me(),
+            }),
+            KeyedListProbe {
+                slots: slots.clone(),
+                parent: spawn_cx
+                    .ok()
+                    .map(|spawn_cx| (spawn_cx.parent_entity, spawn_cx.child_order.clone())),
+                is_changed: Cell::new(true),
+            },
+        )
+    }
+}
+
+/// Composable to sort the entities of a [`KeyedList`] by the order of its items.
+///
+/// Items are composed in the order of their slots, so this runs after them
+/// to move their entities to the order of the list's items.
+struct KeyedListProbe<K> {
+    slots: Vec<KeyedSlot<K>>,
+    parent: Option<(Entity, Rc<ChildOrder>)>,
+    is_changed: Cell<bool>,
+}
+
+unsafe impl<K> Data for KeyedListProbe<K> {}
+
+impl<K: 'static> Compose for KeyedListProbe<K> {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.set_changed();
+
+        let Some((parent_entity, child_order)) = &cx.me().parent else {
+            return;
+        };
+
+        let mut entities = child_order.entities.borrow_mut();
+        let child_set: HashSet<Entity> = entities.iter().copied().collect();
+
+        let mut indices: HashMap<Entity, usize> = HashMap::new();
+        for slot in &cx.me().slots {
+            // Forget any entities of this slot that were despawned since the last composition.
+            let mut slot_entities = slot.0.entities.borrow_mut();
+            slot_entities.retain(|entity| child_set.contains(entity));
+
+            if slot.0.key.borrow().is_some() {
+                for entity in slot_entities.iter() {
+                    indices.insert(*entity, slot.0.index.get());
+                }
+            }
+        }
+
+        let start = entities
+            .iter()
+            .position(|entity| indices.contains_key(entity));
+        let end = entities
+            .iter()
+            .rposition(|entity| indices.contains_key(entity));
+
+        // This sort is stable, so the entities of each item are kept in their composition order.
+        if let (Some(start), Some(end)) = (start, end) {
+            entities[start..=end]
+                .sort_by_key(|entity| indices.get(entity).copied().unwrap_or(usize::MAX));
+        }
+
+        if cx.me().is_changed.take() {
+            child_order.queue_reorder(*parent_entity);
+        }
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
+    }
+}
+
+struct KeyedSlotState<K> {
+    key: RefCell<Option<K>>,
+    index: Cell<usize>,
+    /// Root entities spawned by this slot's item (see [`SpawnContext::keyed_entities`]).
+    entities: Rc<RefCell<Vec<Entity>>>,
+}
+
+struct KeyedSlot<K>(Rc<KeyedSlotState<K>>);
+
+impl<K> Clone for KeyedSlot<K> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+unsafe impl<K> Data for KeyedSlot<K> {}
+
+/// Composable of the slots of a [`KeyedList`], starting at `index`.
+///
+/// Slots are composed as a binary tree (with the children of slot `i` at `2i + 1` and `2i + 2`),
+/// so each slot keeps its scope for as long as it exists, with a depth of `log2(n)`.
+struct KeyedSlots<'a, T, K, C> {
+    slots: Rc<Vec<KeyedSlot<K>>>,
+    index: usize,
+    list: Ref<'a, KeyedList<'a, T, K, C>>,
+}
+
+unsafe impl<T, K, C: Data> Data for KeyedSlots<'_, T, K, C> {}
+
+impl<'a, T, K, C> Compose for KeyedSlots<'a, T, K, C>
+where
+    K: 'static,
+    C: Compose,
+{
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let me = cx.me();
+        let child = |index: usize| {
+            (index < me.slots.len()).then(|| KeyedSlots {
+                slots: me.slots.clone(),
+                index,
+                list: me.list,
+            })
+        };
+
+        (
+            KeyedItem {
+                slot: me.slots[me.index].clone(),
+                list: me.list,
+            },
+            child(2 * me.index + 1),
+            child(2 * me.index + 2),
+        )
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
+    }
+}
+
+struct KeyedItem<'a, T, K, C> {
+    slot: KeyedSlot<K>,
+    list: Ref<'a, KeyedList<'a, T, K, C>>,
+}
+
+unsafe impl<T, K, C: Data> Data for KeyedItem<'_, T, K, C> {}
+
+impl<T, K, C> Compose for KeyedItem<'_, T, K, C>
+where
+    K: 'static,
+    C: Compose,
+{
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let me = cx.me();
+        let slot = &me.slot.0;
+
+        let spawn_cx = use_context::<SpawnContext>(&cx);
+        if let Ok(spawn_cx) = spawn_cx {
+            use_provider(&cx, || SpawnContext {
+                parent_entity: spawn_cx.parent_entity,
+                child_order: spawn_cx.child_order.clone(),
+                keyed_entities: Some(slot.entities.clone()),
+                debug: spawn_cx.debug.clone(),
+            });
+        }
+
+        let index = slot.index.get();
+        let is_mounted = slot.key.borrow().is_some();
+        is_mounted.then(|| (me.list.item_fn)(&me.list.items[index]))
+    }
+
+    fn name() -> Option<Cow<'static, str>> {
+        None
     }
 }
 
-fn use_bundle_inner(cx: ScopeState, spawn: impl FnOnce(&mut World, &mut Option<Entity>)) -> Entity {
+fn use_bundle_inner<'a>(
+    cx: ScopeState<'a>,
+    spawn: impl FnOnce(&mut World, &mut Option<Entity>),
+    on_drop: impl FnOnce(&mut World, Entity) + 'a,
+) -> Entity {
+    let rt_cx = use_ref(cx, RuntimeContext::current);
+
     let mut f_cell = Some(spawn);
     let entity = *use_ref(cx, || {
-        let world = unsafe { RuntimeContext::current().world_mut() };
-
         let mut cell = None;
-        f_cell.take().unwrap()(world, &mut cell);
+        rt_cx.with_world(|world| f_cell.take().unwrap()(world, &mut cell));
         cell.unwrap()
     });
 
     if let Some(f) = f_cell {
-        let world = unsafe { RuntimeContext::current().world_mut() };
-        f(world, &mut Some(entity));
+        rt_cx.with_world(|world| f(world, &mut Some(entity)));
     }
 
     use_drop(cx, move || {
-        let world = unsafe { RuntimeContext::current().world_mut() };
-        world.try_despawn(entity);
+        // The world is unavailable if it's being dropped along with the runtime.
+        rt_cx.try_with_world(|world| on_drop(world, entity));
     });
 
     entity
 }
+
+fn despawn_entity(world: &mut World, entity: Entity) {
+    if let Ok(mut entity_mut) = world.get_entity_mut(entity) {
+        // Remove the entity from its parent's `Children` before despawning it.
+        entity_mut.remove_parent();
+        entity_mut.despawn();
+    }
+}
@@ -0,0 +1,428 @@
+//! Headless testing of compositions.
+//!
+//! This module requires the `testing` feature, which is usually enabled only for tests:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! bevy_mod_actuate = { version = "0.4", features = ["testing"] }
+//! ```
+//!
+//! ```
+//! use actuate::prelude::*;
+//! use bevy::prelude::*;
+//! use bevy_mod_actuate::{prelude::*, testing::CompositionTester};
+//!
+//! #[derive(Data)]
+//! struct Counter;
+//!
+//! impl Compose for Counter {
+//!     fn compose(cx: Scope<Self>) -> impl Compose {
+//!         let count = use_mut(&cx, || 0);
+//!
+//!         spawn((Name::new("count"), Text::new(count.to_string()))).observe(
+//!             move |_trigger: In<Trigger<Pointer<Click>>>| Mut::update(count, |x| *x += 1),
+//!         )
+//!     }
+//! }
+//!
+//! let mut tester = CompositionTester::new(Counter);
+//! let count = tester.find_by_name("count").unwrap();
+//!
+//! tester.click(count).update();
+//! assert_eq!(tester.get::<Text>(count).unwrap().0, "1");
+//! ```
+//!
+//! ## Snapshots
+//!
+//! The entity tree of a composition can be written to a stable RON format with [`snapshot`]
+//! (or [`snapshot_with_values`] to include reflected component values),
+//! and compared against a checked-in file with [`assert_snapshot`].
+//!
+//! ```no_run
+//! # use actuate::prelude::*;
+//! # use bevy::prelude::*;
+//! # use bevy_mod_actuate::{prelude::*, testing::CompositionTester};
+//! # #[derive(Data)]
+//! # struct Counter;
+//! # impl Compose for Counter {
+//! #     fn compose(_cx: Scope<Self>) -> impl Compose {}
+//! # }
+//! let tester = CompositionTester::new(Counter);
+//!
+//! // Set `UPDATE_SNAPSHOTS=1` to write the current snapshot to this file.
+//! tester.assert_snapshot("tests/snapshots/counter.ron");
+//! ```
+
+use crate::{ActuatePlugin, Composition};
+use actuate::prelude::Compose;
+use bevy::{
+    app::App,
+    ecs::{
+        component::{Component, ComponentInfo},
+        reflect::AppTypeRegistry,
+    },
+    picking::{
+        backend::HitData,
+        events::{Click, Pointer},
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::{
+        Children, Entity, Event, Handle, MinimalPlugins, Name, Parent, ReflectComponent, Vec2,
+        World,
+    },
+    reflect::{serde::TypedReflectSerializer, TypeRegistry},
+    render::camera::NormalizedRenderTarget,
+    scene::ron,
+};
+use std::{any::TypeId, env, fmt::Write, fs, path::Path, time::Duration};
+
+/// Tester for a [`Composition`] running in a headless [`App`].
+pub struct CompositionTester {
+    app: App,
+    root: Entity,
+}
+
+impl CompositionTester {
+    /// Create a new tester for `content`, running with [`MinimalPlugins`] and the default [`ActuatePlugin`].
+    ///
+    /// The content is composed during the first frame, which runs before this returns.
+    pub fn new<C>(content: C) -> Self
+    where
+        C: Compose + Send + Sync + 'static,
+    {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ActuatePlugin::default()));
+        Self::from_app(app, content)
+    }
+
+    /// Create a new tester for `content` from an existing `app`.
+    ///
+    /// The app must have an [`ActuatePlugin`] added.
+    /// The content is composed during the first frame, which runs before this returns.
+    pub fn from_app<C>(mut app: App, content: C) -> Self
+    where
+        C: Compose + Send + Sync + 'static,
+    {
+        let root = app.world_mut().spawn(Composition::new(content)).id();
+
+        let mut me = Self { app, root };
+        me.update();
+        me
+    }
+
+    /// Get the entity of the [`Composition`].
+    ///
+    /// Spawned content is added as children of this entity.
+    pub fn root(&self) -> Entity {
+        self.root
+    }
+
+    /// Get the app running the composition.
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// Get a mutable reference to the app running the composition.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Get the world of the app.
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// Get a mutable reference to the world of the app.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Run a frame of the app.
+    pub fn update(&mut self) -> &mut Self {
+        self.app.update();
+        self
+    }
+
+    /// Run `frames` frames of the app.
+    pub fn update_frames(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.update();
+        }
+        self
+    }
+
+    /// Get the children of `entity`, in order.
+    pub fn children(&self, entity: Entity) -> Vec<Entity> {
+        self.world()
+            .get::<Children>(entity)
+            .map(|children| children.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Get every entity spawned under the root, in depth-first order.
+    pub fn entities(&self) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        let mut stack = self.children(self.root);
+        stack.reverse();
+
+        while let Some(entity) = stack.pop() {
+            entities.push(entity);
+            stack.extend(self.children(entity).into_iter().rev());
+        }
+
+        entities
+    }
+
+    /// Get every entity spawned under the root with the component `T`, in depth-first order.
+    pub fn find<T: Component>(&self) -> Vec<Entity> {
+        self.entities()
+            .into_iter()
+            .filter(|entity| self.world().get::<T>(*entity).is_some())
+            .collect()
+    }
+
+    /// Get the first entity spawned under the root with a [`Name`] of `name`, in depth-first order.
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.entities().into_iter().find(|entity| {
+            self.world()
+                .get::<Name>(*entity)
+                .is_some_and(|entity_name| entity_name.as_str() == name)
+        })
+    }
+
+    /// Get the component `T` of `entity`, or `None` if it doesn't exist.
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.world().get::<T>(entity)
+    }
+
+    /// Trigger `event` for the observers of `entity`.
+    ///
+    /// Any state updates from the observers are composed on the next [`CompositionTester::update`].
+    pub fn trigger(&mut self, entity: Entity, event: impl Event) -> &mut Self {
+        self.world_mut().trigger_targets(event, entity);
+        self
+    }
+
+    /// Simulate a primary-button [`Click`] on `entity`.
+    ///
+    /// Any state updates from the observers are composed on the next [`CompositionTester::update`].
+    pub fn click(&mut self, entity: Entity) -> &mut Self {
+        let location = Location {
+            target: NormalizedRenderTarget::Image(Handle::default()),
+            position: Vec2::ZERO,
+        };
+        let click = Click {
+            button: PointerButton::Primary,
+            hit: HitData::new(Entity::PLACEHOLDER, 0., None, None),
+            duration: Duration::ZERO,
+        };
+
+        self.trigger(
+            entity,
+            Pointer::new(entity, PointerId::Mouse, location, click),
+        )
+    }
+
+    /// Write a [`snapshot`] of the entity tree under the root.
+    pub fn snapshot(&self) -> String {
+        snapshot(self.world(), self.root)
+    }
+
+    /// Write a [`snapshot_with_values`] of the entity tree under the root.
+    pub fn snapshot_with_values(&self) -> String {
+        snapshot_with_values(self.world(), self.root)
+    }
+
+    /// Assert a [`snapshot`] of the entity tree under the root matches the file at `path`.
+    ///
+    /// See [`assert_snapshot`] for more.
+    #[track_caller]
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        assert_snapshot(path, &self.snapshot());
+    }
+}
+
+/// Write a snapshot of the entity tree under `root` (such as a [`Composition`]).
+///
+/// Each entity is written in RON with the sorted type names of its components, followed by its children in order.
+/// Entity IDs, hierarchy components ([`Parent`] and [`Children`]), and Bevy's internal observer bookkeeping
+/// are left out, so snapshots only change when the shape of the tree changes.
+///
+/// Components registered in the [`AppTypeRegistry`] are named by their stable [`TypePath`](bevy::reflect::TypePath).
+/// Other components are named by [`std::any::type_name`], which isn't guaranteed to be stable between
+/// compiler versions, so register your components (e.g. with `App::register_type`) to keep snapshots stable.
+pub fn snapshot(world: &World, root: Entity) -> String {
+    write_snapshot(world, root, false)
+}
+
+/// Write a snapshot of the entity tree under `root` (such as a [`Composition`]), including component values.
+///
+/// This is the same as [`snapshot`], except each component is written with its value serialized from
+/// the [`AppTypeRegistry`].
+/// Components that aren't registered with [`ReflectComponent`] (or fail to serialize) are written as `()`.
+///
+/// # Panics
+/// Panics if the world doesn't have an [`AppTypeRegistry`].
+pub fn snapshot_with_values(world: &World, root: Entity) -> String {
+    write_snapshot(world, root, true)
+}
+
+/// Assert `actual` matches the snapshot stored at `path`, relative to the package's manifest directory.
+///
+/// If the `UPDATE_SNAPSHOTS` environment variable is set, `actual` is written to `path` instead.
+///
+/// # Panics
+/// Panics if the snapshot doesn't exist, or doesn't match `actual`.
+#[track_caller]
+pub fn assert_snapshot(path: impl AsRef<Path>, actual: &str) {
+    let path = env::var_os("CARGO_MANIFEST_DIR")
+        .map(|dir| Path::new(&dir).join(path.as_ref()))
+        .unwrap_or_else(|| path.as_ref().to_path_buf());
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create the snapshot directory.");
+        }
+        fs::write(&path, actual).expect("Failed to write the snapshot.");
+        return;
+    }
+
+    let Ok(expected) = fs::read_to_string(&path) else {
+        panic!(
+            "Snapshot `{}` doesn't exist (set `UPDATE_SNAPSHOTS=1` to create it).\n\n{actual}",
+            path.display()
+        );
+    };
+
+    assert!(
+        expected == actual,
+        "Snapshot `{}` doesn't match (set `UPDATE_SNAPSHOTS=1` to update it).\n\nExpected:\n{expected}\nActual:\n{actual}",
+        path.display()
+    );
+}
+
+fn write_snapshot(world: &World, root: Entity, has_values: bool) -> String {
+    let registry = if has_values {
+        Some(world.resource::<AppTypeRegistry>().read())
+    } else {
+        world
+            .get_resource::<AppTypeRegistry>()
+            .map(|registry| registry.read())
+    };
+
+    let mut output = String::new();
+    let cx = SnapshotContext {
+        world,
+        registry: registry.as_deref(),
+        has_values,
+    };
+    write_children(&cx, root, 0, &mut output);
+    output.push('\n');
+    output
+}
+
+/// Bevy's internal bookkeeping of entity observers, which is private to Bevy so it can only be matched by name.
+const OBSERVED_BY: &str = "bevy_ecs::observer::entity_observer::ObservedBy";
+
+struct SnapshotContext<'a> {
+    world: &'a World,
+    /// Registry to name components by their type paths, and to serialize their values.
+    registry: Option<&'a TypeRegistry>,
+    /// `true` if component values are written.
+    has_values: bool,
+}
+
+impl SnapshotContext<'_> {
+    /// Get the name of a component, preferring its registered type path.
+    fn name(&self, info: &ComponentInfo) -> String {
+        info.type_id()
+            .zip(self.registry)
+            .and_then(|(type_id, registry)| registry.get(type_id))
+            .map(|registration| registration.type_info().type_path().to_string())
+            .unwrap_or_else(|| info.name().to_string())
+    }
+}
+
+fn write_children(cx: &SnapshotContext, entity: Entity, depth: usize, output: &mut String) {
+    let children = cx
+        .world
+        .get::<Children>(entity)
+        .map(|children| children.to_vec())
+        .unwrap_or_default();
+
+    if children.is_empty() {
+        output.push_str("[]");
+        return;
+    }
+
+    output.push_str("[\n");
+    for child in children {
+        indent(depth + 1, output);
+        write_entity(cx, child, depth + 1, output);
+        output.push_str(",\n");
+    }
+    indent(depth, output);
+    output.push(']');
+}
+
+fn write_entity(cx: &SnapshotContext, entity: Entity, depth: usize, output: &mut String) {
+    let hierarchy = [TypeId::of::<Parent>(), TypeId::of::<Children>()];
+    let mut components: Vec<_> = cx
+        .world
+        .inspect_entity(entity)
+        .filter(|info| {
+            !info
+                .type_id()
+                .is_some_and(|type_id| hierarchy.contains(&type_id))
+                && info.name() != OBSERVED_BY
+        })
+        .map(|info| (cx.name(info), info))
+        .collect();
+    components.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    output.push_str("Entity(\n");
+    indent(depth + 1, output);
+
+    if let Some(registry) = cx.registry.filter(|_| cx.has_values) {
+        output.push_str("components: {\n");
+        for (name, info) in components {
+            let value = info
+                .type_id()
+                .and_then(|type_id| registry.get_type_data::<ReflectComponent>(type_id))
+                .and_then(|reflect_component| reflect_component.reflect(cx.world.entity(entity)))
+                .and_then(|value| {
+                    let serializer =
+                        TypedReflectSerializer::new(value.as_partial_reflect(), registry);
+                    ron::to_string(&serializer).ok()
+                })
+                .unwrap_or_else(|| String::from("()"));
+
+            indent(depth + 2, output);
+            writeln!(output, "{name:?}: {value},").unwrap();
+        }
+        indent(depth + 1, output);
+        output.push_str("},\n");
+    } else {
+        output.push_str("components: [\n");
+        for (name, _) in components {
+            indent(depth + 2, output);
+            writeln!(output, "{name:?},").unwrap();
+        }
+        indent(depth + 1, output);
+        output.push_str("],\n");
+    }
+
+    indent(depth + 1, output);
+    output.push_str("children: ");
+    write_children(cx, entity, depth + 1, output);
+    output.push_str(",\n");
+
+    indent(depth, output);
+    output.push(')');
+}
+
+fn indent(depth: usize, output: &mut String) {
+    for _ in 0..depth {
+        output.push_str("    ");
+    }
+}
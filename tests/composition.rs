@@ -0,0 +1,813 @@
+use actuate::prelude::{Mut, *};
+use bevy::prelude::*;
+use bevy_mod_actuate::prelude::*;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+#[derive(Component, Clone)]
+struct Item;
+
+#[derive(Resource, Default)]
+struct ListenerRuns(usize);
+
+#[derive(Data)]
+struct Tree;
+
+impl Compose for Tree {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        use_world(&cx, |mut runs: ResMut<ListenerRuns>| runs.0 += 1);
+
+        spawn_with(Item, (spawn(Item), spawn(Item)))
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(ActuatePlugin::default())
+        .init_resource::<ListenerRuns>();
+    app
+}
+
+fn items(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<Item>>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn it_tears_down_despawned_compositions() {
+    let mut app = app();
+    let root = app.world_mut().spawn(Composition::new(Tree)).id();
+
+    app.update();
+    app.update();
+    assert_eq!(items(&mut app), 3);
+    let runs = app.world().resource::<ListenerRuns>().0;
+
+    app.world_mut().entity_mut(root).despawn();
+
+    app.update();
+    app.update();
+    assert_eq!(items(&mut app), 0);
+    assert_eq!(app.world().resource::<ListenerRuns>().0, runs);
+}
+
+#[test]
+fn it_tears_down_removed_compositions() {
+    let mut app = app();
+    let root = app.world_mut().spawn(Composition::new(Tree)).id();
+
+    app.update();
+    assert_eq!(items(&mut app), 3);
+
+    app.world_mut()
+        .entity_mut(root)
+        .remove::<Composition<Tree>>();
+
+    app.update();
+    let runs = app.world().resource::<ListenerRuns>().0;
+    app.update();
+
+    assert_eq!(items(&mut app), 0);
+    assert_eq!(app.world().resource::<ListenerRuns>().0, runs);
+    assert!(app.world().get_entity(root).is_ok());
+}
+
+#[test]
+fn it_replaces_compositions() {
+    let mut app = app();
+    let root = app.world_mut().spawn(Composition::new(Tree)).id();
+
+    app.update();
+    app.world_mut()
+        .entity_mut(root)
+        .insert(Composition::new(Tree));
+    app.update();
+
+    assert_eq!(items(&mut app), 3);
+}
+
+#[derive(Data)]
+struct Ticking {
+    composes: Arc<AtomicUsize>,
+}
+
+impl Compose for Ticking {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.me().composes.fetch_add(1, Ordering::SeqCst);
+
+        // Re-compose on every frame.
+        let ticks = use_mut(&cx, || 0);
+        use_world(&cx, move || Mut::update(ticks, |ticks| *ticks += 1));
+
+        spawn(Item)
+    }
+}
+
+#[test]
+fn it_skips_compositions_despawned_before_composing() {
+    let mut app = app();
+    let composes = Arc::new(AtomicUsize::new(0));
+    app.world_mut().spawn(Composition::new(Ticking {
+        composes: composes.clone(),
+    }));
+
+    app.update();
+    app.update();
+    let count = composes.load(Ordering::SeqCst);
+    assert!(count > 1);
+
+    // Despawn the composition after its listener queued an update, but before it's composed.
+    app.add_systems(
+        Update,
+        (|mut commands: Commands, query: Query<Entity, With<Composition<Ticking>>>| {
+            for entity in &query {
+                commands.entity(entity).despawn();
+            }
+        })
+        .after(ActuateSet::RunListeners)
+        .before(ActuateSet::Compose),
+    );
+
+    app.update();
+    assert_eq!(composes.load(Ordering::SeqCst), count);
+    assert_eq!(items(&mut app), 0);
+}
+
+#[derive(bevy::ecs::schedule::ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct Ui;
+
+#[test]
+fn it_composes_in_a_custom_schedule() {
+    let mut app = App::new();
+    app.init_schedule(Ui)
+        .add_plugins(ActuatePlugin::default().with_schedule(Ui))
+        .init_resource::<ListenerRuns>();
+    app.world_mut().spawn(Composition::new(Tree));
+
+    app.update();
+    assert_eq!(items(&mut app), 0);
+
+    app.world_mut().run_schedule(Ui);
+    assert_eq!(items(&mut app), 3);
+}
+
+#[test]
+fn it_isolates_runtimes_per_app() {
+    let mut a = app();
+    let mut b = app();
+    a.world_mut().spawn(Composition::new(Tree));
+
+    for _ in 0..3 {
+        a.update();
+        b.update();
+    }
+
+    assert_eq!(items(&mut a), 3);
+    assert!(a.world().resource::<ListenerRuns>().0 > 0);
+    assert_eq!(items(&mut b), 0);
+    assert_eq!(b.world().resource::<ListenerRuns>().0, 0);
+
+    drop(a);
+    b.world_mut().spawn(Composition::new(Tree));
+    b.update();
+    assert_eq!(items(&mut b), 3);
+}
+
+#[derive(Component, Clone)]
+struct Highlight;
+
+#[derive(Resource, Clone)]
+struct Highlighted(bool);
+
+#[derive(Data)]
+struct Button;
+
+impl Compose for Button {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let highlighted = use_resource::<Highlighted>(&cx);
+
+        if highlighted.as_ref().unwrap().0 {
+            spawn((Item, Highlight))
+        } else {
+            spawn(Item)
+        }
+    }
+}
+
+fn highlights(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), (With<Item>, With<Highlight>)>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn it_removes_stale_components() {
+    let mut app = app();
+    app.insert_resource(Highlighted(true));
+    app.world_mut().spawn(Composition::new(Button));
+
+    app.update();
+    assert_eq!(items(&mut app), 1);
+    assert_eq!(highlights(&mut app), 1);
+
+    app.world_mut().resource_mut::<Highlighted>().0 = false;
+    app.update();
+    assert_eq!(items(&mut app), 1);
+    assert_eq!(highlights(&mut app), 0);
+
+    app.world_mut().resource_mut::<Highlighted>().0 = true;
+    app.update();
+    assert_eq!(highlights(&mut app), 1);
+}
+
+#[derive(Component, Clone, Default)]
+struct Req;
+
+#[derive(Component, Clone)]
+#[require(Req)]
+struct Needs;
+
+#[derive(Resource, Clone)]
+struct Explicit(bool);
+
+#[derive(Data)]
+struct Requirer;
+
+impl Compose for Requirer {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let explicit = use_resource::<Explicit>(&cx);
+
+        if explicit.as_ref().unwrap().0 {
+            spawn_with((Req, Needs), ())
+        } else {
+            spawn_with((Needs,), ())
+        }
+    }
+}
+
+#[test]
+fn it_keeps_required_components() {
+    let mut app = app();
+    app.insert_resource(Explicit(true));
+    app.world_mut().spawn(Composition::new(Requirer));
+
+    app.update();
+    app.world_mut().resource_mut::<Explicit>().0 = false;
+    app.update();
+
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Needs>>()
+        .single(app.world());
+    assert!(app.world().get::<Req>(entity).is_some());
+}
+
+#[derive(Component, Clone, PartialEq)]
+struct Row(usize);
+
+#[derive(Component, Clone, PartialEq)]
+struct Value(usize);
+
+#[derive(Resource, Clone)]
+struct Count(usize);
+
+#[derive(Data)]
+struct DiffRow;
+
+impl Compose for DiffRow {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let count = use_resource::<Count>(&cx);
+
+        spawn_diff((Row(0), Value(count.as_ref().unwrap().0)))
+    }
+}
+
+fn last_changed<C: Component>(app: &mut App) -> Vec<bevy::ecs::component::Tick> {
+    app.world_mut()
+        .query::<bevy::prelude::Ref<C>>()
+        .iter(app.world())
+        .map(|component| component.last_changed())
+        .collect()
+}
+
+#[test]
+fn it_only_inserts_changed_components() {
+    let mut app = app();
+    app.insert_resource(Count(0));
+    app.world_mut().spawn(Composition::new(DiffRow));
+
+    app.update();
+    let rows = last_changed::<Row>(&mut app);
+    let values = last_changed::<Value>(&mut app);
+
+    app.world_mut().resource_mut::<Count>().0 = 1;
+    app.update();
+    assert_eq!(last_changed::<Row>(&mut app), rows);
+    assert_ne!(last_changed::<Value>(&mut app), values);
+    assert_eq!(app.world_mut().query::<&Value>().single(app.world()).0, 1);
+}
+
+#[derive(Resource, Clone)]
+struct Keys(Vec<usize>);
+
+#[derive(Data)]
+struct KeyedRows;
+
+impl Compose for KeyedRows {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let keys = use_resource::<Keys>(&cx);
+
+        spawn_with(
+            Item,
+            spawn_keyed_list(
+                keys.as_ref().unwrap().0.clone(),
+                |key| *key,
+                |key| spawn(Row(*key)),
+            ),
+        )
+    }
+}
+
+fn rows(app: &mut App) -> Vec<(usize, Entity)> {
+    let parent = app
+        .world_mut()
+        .query_filtered::<Entity, With<Item>>()
+        .single(app.world());
+
+    app.world()
+        .get::<Children>(parent)
+        .unwrap()
+        .iter()
+        .map(|entity| (app.world().get::<Row>(*entity).unwrap().0, *entity))
+        .collect()
+}
+
+fn row_keys(app: &mut App) -> Vec<usize> {
+    rows(app).into_iter().map(|(key, _)| key).collect()
+}
+
+#[test]
+fn it_keeps_keyed_entities_in_order() {
+    let mut app = app();
+    app.insert_resource(Keys(vec![1, 2, 3]));
+    app.world_mut().spawn(Composition::new(KeyedRows));
+
+    app.update();
+    app.update();
+    let before = rows(&mut app);
+    assert_eq!(
+        before.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+
+    app.world_mut().resource_mut::<Keys>().0 = vec![3, 4, 1];
+    app.update();
+    let after = rows(&mut app);
+    assert_eq!(
+        after.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        [3, 4, 1]
+    );
+    assert_eq!(after[0].1, before[2].1);
+    assert_eq!(after[2].1, before[0].1);
+    assert!(app.world().get_entity(before[1].1).is_err());
+
+    app.world_mut().resource_mut::<Keys>().0 = vec![2, 3, 5, 1];
+    app.update();
+    let last = rows(&mut app);
+    assert_eq!(
+        last.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        [2, 3, 5, 1]
+    );
+    assert_eq!(last[1].1, after[0].1);
+    assert_eq!(last[3].1, after[2].1);
+    assert!(app.world().get_entity(after[1].1).is_err());
+    assert_eq!(items(&mut app), 1);
+}
+
+#[test]
+fn it_reorders_long_keyed_lists() {
+    let mut app = app();
+    app.insert_resource(Keys((0..10).collect()));
+    app.world_mut().spawn(Composition::new(KeyedRows));
+
+    app.update();
+    app.update();
+    let before = rows(&mut app);
+    assert_eq!(row_keys(&mut app), (0..10).collect::<Vec<_>>());
+
+    app.world_mut().resource_mut::<Keys>().0 = (0..10).rev().step_by(2).collect();
+    app.update();
+    let after = rows(&mut app);
+    assert_eq!(row_keys(&mut app), [9, 7, 5, 3, 1]);
+    for (key, entity) in &after {
+        assert_eq!(*entity, before[*key].1);
+    }
+    for (_, entity) in before.iter().step_by(2) {
+        assert!(app.world().get_entity(*entity).is_err());
+    }
+}
+
+#[test]
+fn it_skips_duplicate_keys() {
+    let mut app = app();
+    app.insert_resource(Keys(vec![1, 2, 1]));
+    app.world_mut().spawn(Composition::new(KeyedRows));
+
+    app.update();
+    app.update();
+    let before = rows(&mut app);
+    assert_eq!(row_keys(&mut app), [1, 2]);
+
+    app.world_mut().resource_mut::<Keys>().0 = vec![2, 2, 1];
+    app.update();
+    let after = rows(&mut app);
+    assert_eq!(row_keys(&mut app), [2, 1]);
+    assert_eq!(after[0].1, before[1].1);
+    assert_eq!(after[1].1, before[0].1);
+}
+
+#[derive(Data)]
+struct KeyedPairs;
+
+impl Compose for KeyedPairs {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let keys = use_resource::<Keys>(&cx);
+
+        spawn_with(
+            Item,
+            spawn_keyed_list(
+                keys.as_ref().unwrap().0.clone(),
+                |key| *key,
+                |key| (spawn(Row(*key)), spawn(Row(*key + 10))),
+            ),
+        )
+    }
+}
+
+#[test]
+fn it_keeps_multiple_roots_of_keyed_items_together() {
+    let mut app = app();
+    app.insert_resource(Keys(vec![1, 2, 3]));
+    app.world_mut().spawn(Composition::new(KeyedPairs));
+
+    app.update();
+    app.update();
+    assert_eq!(row_keys(&mut app), [1, 11, 2, 12, 3, 13]);
+
+    app.world_mut().resource_mut::<Keys>().0 = vec![3, 1, 4];
+    app.update();
+    assert_eq!(row_keys(&mut app), [3, 13, 1, 11, 4, 14]);
+}
+
+#[derive(Resource, Clone)]
+struct Shown(bool);
+
+#[derive(Data)]
+struct OptionalRow;
+
+impl Compose for OptionalRow {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let shown = use_resource::<Shown>(&cx);
+
+        spawn_with(
+            Item,
+            (
+                spawn(Row(0)),
+                if shown.as_ref().unwrap().0 {
+                    Some(spawn(Row(1)))
+                } else {
+                    None
+                },
+                spawn(Row(2)),
+            ),
+        )
+    }
+}
+
+#[test]
+fn it_keeps_optional_children_in_order() {
+    let mut app = app();
+    app.insert_resource(Shown(false));
+    app.world_mut().spawn(Composition::new(OptionalRow));
+
+    app.update();
+    assert_eq!(row_keys(&mut app), [0, 2]);
+
+    app.world_mut().resource_mut::<Shown>().0 = true;
+    app.update();
+    assert_eq!(row_keys(&mut app), [0, 1, 2]);
+
+    app.world_mut().resource_mut::<Shown>().0 = false;
+    app.update();
+    assert_eq!(row_keys(&mut app), [0, 2]);
+
+    app.world_mut().resource_mut::<Shown>().0 = true;
+    app.update();
+    assert_eq!(row_keys(&mut app), [0, 1, 2]);
+}
+
+#[derive(Event)]
+struct Ping;
+
+#[derive(Resource, Default)]
+struct Seen(Vec<usize>);
+
+#[derive(Data)]
+struct Observed;
+
+impl Compose for Observed {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let value = use_resource::<Count>(&cx).as_ref().unwrap().0;
+        spawn(Item)
+            .observe(move |_: In<Trigger<Ping>>, mut seen: ResMut<Seen>| seen.0.push(value))
+            .observe_with_deps((), move |_: In<Trigger<Ping>>, mut seen: ResMut<Seen>| {
+                seen.0.push(value + 100)
+            })
+    }
+}
+
+fn ping(app: &mut App) -> Vec<usize> {
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Item>>()
+        .single(app.world());
+    app.world_mut().trigger_targets(Ping, entity);
+
+    let mut seen = std::mem::take(&mut app.world_mut().resource_mut::<Seen>().0);
+    seen.sort();
+    seen
+}
+
+#[test]
+fn it_rebinds_observers() {
+    let mut app = app();
+    app.insert_resource(Count(0)).init_resource::<Seen>();
+    app.world_mut().spawn(Composition::new(Observed));
+
+    app.update();
+    assert_eq!(ping(&mut app), [0, 100]);
+
+    app.world_mut().resource_mut::<Count>().0 = 1;
+    app.update();
+    assert_eq!(ping(&mut app), [1, 100]);
+
+    let observers = app
+        .world_mut()
+        .query::<&bevy::ecs::observer::Observer>()
+        .iter(app.world())
+        .count();
+    assert_eq!(observers, 2);
+}
+
+/// Number of observers in the `ObservedBy` of `entity`.
+fn observed_by(app: &App, entity: Entity) -> usize {
+    let id = app
+        .world()
+        .components()
+        .iter()
+        .find(|info| info.name().ends_with("::ObservedBy"))
+        .unwrap()
+        .id();
+    let ptr = app.world().entity(entity).get_by_id(id).unwrap();
+
+    // Safety: `ObservedBy` is private to Bevy, but it's a newtype of `Vec<Entity>`.
+    unsafe { ptr.deref::<Vec<Entity>>() }.len()
+}
+
+#[test]
+fn it_rebinds_observers_in_place() {
+    let mut app = app();
+    app.insert_resource(Count(0)).init_resource::<Seen>();
+    app.world_mut().spawn(Composition::new(Observed));
+
+    app.update();
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Item>>()
+        .single(app.world());
+    assert_eq!(observed_by(&app, entity), 2);
+
+    for count in 1..=50 {
+        app.world_mut().resource_mut::<Count>().0 = count;
+        app.update();
+    }
+    assert_eq!(observed_by(&app, entity), 2);
+    assert_eq!(ping(&mut app), [50, 100]);
+}
+
+#[derive(Component)]
+struct Target;
+
+#[derive(Data)]
+struct Targeted {
+    target: Entity,
+}
+
+impl Compose for Targeted {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let shown = use_resource::<Shown>(&cx);
+
+        let target = cx.me().target;
+        if shown.as_ref().unwrap().0 {
+            Some(
+                spawn((Highlight, Value(1)))
+                    .with_target(target)
+                    .observe(|_: In<Trigger<Ping>>| {}),
+            )
+        } else {
+            None
+        }
+    }
+}
+
+#[test]
+fn it_keeps_target_entities() {
+    let mut app = app();
+    app.insert_resource(Shown(true));
+    let target = app.world_mut().spawn(Target).id();
+    app.world_mut().spawn(Composition::new(Targeted { target }));
+
+    app.update();
+    app.update();
+    assert!(app.world().get::<Highlight>(target).is_some());
+
+    app.world_mut().resource_mut::<Shown>().0 = false;
+    app.update();
+
+    let observers = app
+        .world_mut()
+        .query::<&bevy::ecs::observer::Observer>()
+        .iter(app.world())
+        .count();
+    assert_eq!(observers, 0);
+    assert!(app.world().get::<Target>(target).is_some());
+    assert!(app.world().get::<Highlight>(target).is_none());
+    assert!(app.world().get::<Value>(target).is_none());
+}
+
+#[test]
+fn it_keeps_existing_components_of_target_entities() {
+    let mut app = app();
+    app.insert_resource(Shown(true));
+    let target = app.world_mut().spawn((Target, Value(0))).id();
+    app.world_mut().spawn(Composition::new(Targeted { target }));
+
+    app.update();
+    app.update();
+    assert_eq!(app.world().get::<Value>(target).unwrap().0, 1);
+
+    app.world_mut().resource_mut::<Shown>().0 = false;
+    app.update();
+
+    assert!(app.world().get::<Highlight>(target).is_none());
+    assert!(app.world().get::<Value>(target).is_some());
+}
+
+#[derive(Data)]
+struct ShrinkingTarget {
+    target: Entity,
+}
+
+impl Compose for ShrinkingTarget {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let shown = use_resource::<Shown>(&cx);
+
+        let target = cx.me().target;
+        if shown.as_ref().unwrap().0 {
+            spawn((Highlight, Value(1))).with_target(target)
+        } else {
+            spawn(Highlight).with_target(target)
+        }
+    }
+}
+
+#[test]
+fn it_keeps_existing_components_of_shrinking_targets() {
+    let mut app = app();
+    app.insert_resource(Shown(true));
+    let existing = app.world_mut().spawn((Target, Value(0))).id();
+    let added = app.world_mut().spawn(Target).id();
+    app.world_mut()
+        .spawn(Composition::new(ShrinkingTarget { target: existing }));
+    app.world_mut()
+        .spawn(Composition::new(ShrinkingTarget { target: added }));
+
+    app.update();
+    app.update();
+    assert!(app.world().get::<Value>(existing).is_some());
+    assert!(app.world().get::<Value>(added).is_some());
+
+    app.world_mut().resource_mut::<Shown>().0 = false;
+    app.update();
+
+    assert!(app.world().get::<Highlight>(existing).is_some());
+    assert!(app.world().get::<Value>(existing).is_some());
+    assert!(app.world().get::<Highlight>(added).is_some());
+    assert!(app.world().get::<Value>(added).is_none());
+}
+
+#[derive(Resource, Clone)]
+struct SpawnTarget(Option<Entity>);
+
+#[derive(Data)]
+struct Retargeted;
+
+impl Compose for Retargeted {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let target = use_resource::<SpawnTarget>(&cx);
+
+        let mut spawn = spawn((Highlight, Value(1)));
+        spawn.set_target(target.as_ref().unwrap().0);
+        spawn
+    }
+}
+
+fn highlighted(app: &mut App) -> Vec<Entity> {
+    app.world_mut()
+        .query_filtered::<Entity, With<Highlight>>()
+        .iter(app.world())
+        .collect()
+}
+
+#[test]
+fn it_respawns_when_the_target_changes() {
+    let mut app = app();
+    app.insert_resource(SpawnTarget(None));
+    let first = app.world_mut().spawn(Target).id();
+    let second = app.world_mut().spawn((Target, Value(0))).id();
+    app.world_mut().spawn(Composition::new(Retargeted));
+
+    app.update();
+    let spawned = highlighted(&mut app);
+    assert_eq!(spawned.len(), 1);
+    assert!(app.world().get::<Target>(spawned[0]).is_none());
+
+    app.world_mut().resource_mut::<SpawnTarget>().0 = Some(first);
+    app.update();
+    assert!(app.world().get_entity(spawned[0]).is_err());
+    assert_eq!(highlighted(&mut app), [first]);
+    assert_eq!(app.world().get::<Value>(first).unwrap().0, 1);
+
+    app.world_mut().resource_mut::<SpawnTarget>().0 = Some(second);
+    app.update();
+    assert_eq!(highlighted(&mut app), [second]);
+    assert!(app.world().get::<Value>(first).is_none());
+    assert_eq!(app.world().get::<Value>(second).unwrap().0, 1);
+
+    app.world_mut().resource_mut::<SpawnTarget>().0 = None;
+    app.update();
+    let respawned = highlighted(&mut app);
+    assert_eq!(respawned.len(), 1);
+    assert!(app.world().get::<Target>(respawned[0]).is_none());
+    assert!(app.world().get::<Target>(first).is_some());
+    assert!(app.world().get::<Target>(second).is_some());
+    assert!(app.world().get::<Value>(second).is_some());
+}
+
+#[derive(Component, Clone)]
+struct Linked(Entity);
+
+#[derive(Data)]
+struct Linker;
+
+impl Compose for Linker {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let linked = use_mut(&cx, || None);
+        let value = use_resource::<Count>(&cx).as_ref().unwrap().0;
+        (
+            spawn(Item)
+                .on_spawn(move |entity| Mut::set(linked, Some(entity.id())))
+                .on_update(move |mut entity| {
+                    entity.insert(Value(value));
+                }),
+            linked.map(|entity| spawn(Linked(entity))),
+        )
+    }
+}
+
+#[test]
+fn it_exposes_spawned_entities() {
+    let mut app = app();
+    app.insert_resource(Count(1));
+    app.world_mut().spawn(Composition::new(Linker));
+
+    app.update();
+    app.update();
+    let (entity, value) = app
+        .world_mut()
+        .query_filtered::<(Entity, &Value), With<Item>>()
+        .single(app.world());
+    assert_eq!(value.0, 1);
+
+    let linked = app.world_mut().query::<&Linked>().single(app.world()).0;
+    assert_eq!(linked, entity);
+
+    app.world_mut().resource_mut::<Count>().0 = 2;
+    app.update();
+    assert_eq!(app.world().get::<Value>(entity).unwrap().0, 2);
+}
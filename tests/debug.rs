@@ -0,0 +1,257 @@
+use actuate::prelude::{Mut, *};
+use bevy::prelude::*;
+use bevy_mod_actuate::{
+    debug::{inspect, ComposableInfo, CompositionTree, DebugPlugin},
+    prelude::*,
+    testing::CompositionTester,
+};
+
+#[derive(Component, Clone)]
+struct Label(String);
+
+#[derive(Data)]
+struct Watcher;
+
+impl Compose for Watcher {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        use_world(&cx, || {});
+    }
+}
+
+#[derive(Data)]
+struct Panel;
+
+impl Compose for Panel {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let count = use_mut(&cx, || 0);
+
+        spawn_with(
+            Name::new("panel"),
+            (
+                spawn(Label(count.to_string())).observe(
+                    move |_trigger: In<Trigger<Pointer<Click>>>| Mut::update(count, |x| *x += 1),
+                ),
+                Watcher,
+            ),
+        )
+    }
+}
+
+fn tester() -> CompositionTester {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        ActuatePlugin::default(),
+        DebugPlugin::default(),
+    ));
+    CompositionTester::from_app(app, Panel)
+}
+
+#[test]
+fn it_inspects_compositions() {
+    let mut tester = tester();
+    let panel = tester.find_by_name("panel").unwrap();
+    let label = tester.find::<Label>()[0];
+
+    let compositions = inspect(tester.world());
+    assert_eq!(compositions.len(), 1);
+
+    let composition = &compositions[0];
+    assert_eq!(composition.entity, tester.root());
+    assert!(composition.name.ends_with("Panel"));
+    assert_eq!(composition.composes, 1);
+    assert_eq!(composition.listeners, 1);
+    assert_eq!(composition.spawns.len(), 1);
+
+    let panel_info = &composition.spawns[0];
+    assert_eq!(panel_info.entity, panel);
+    assert_eq!(panel_info.bundle, std::any::type_name::<Name>());
+    assert!(panel_info.content.contains("Watcher"));
+    assert_eq!(panel_info.content_composes, 1);
+    assert_eq!(panel_info.listeners, 1);
+    assert_eq!(panel_info.children.len(), 1);
+
+    let label_info = &panel_info.children[0];
+    assert_eq!(label_info.entity, label);
+    assert_eq!(label_info.composes, 1);
+    assert_eq!(label_info.listeners, 0);
+
+    tester.click(label).update();
+    assert_eq!(tester.get::<Label>(label).unwrap().0, "1");
+
+    let composition = &inspect(tester.world())[0];
+    assert_eq!(composition.composes, 2);
+    assert_eq!(composition.spawns[0].content_composes, 2);
+    assert_eq!(composition.spawns[0].children[0].composes, 2);
+
+    tester.update_frames(2);
+    assert_eq!(inspect(tester.world())[0].composes, 2);
+}
+
+#[derive(Resource, Clone)]
+struct Ticks(usize);
+
+#[derive(Data)]
+struct Ticker;
+
+impl Compose for Ticker {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let ticks = use_resource::<Ticks>(&cx);
+        spawn(Label(ticks.as_ref().unwrap().0.to_string()))
+    }
+}
+
+#[derive(Data)]
+struct Clock;
+
+impl Compose for Clock {
+    fn compose(_cx: Scope<Self>) -> impl Compose {
+        (spawn(Label(String::from("clock"))), Ticker)
+    }
+}
+
+#[test]
+fn it_counts_compositions_of_nested_content() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        ActuatePlugin::default(),
+        DebugPlugin::default(),
+    ))
+    .insert_resource(Ticks(0));
+    let mut tester = CompositionTester::from_app(app, Clock);
+
+    tester.world_mut().resource_mut::<Ticks>().0 = 1;
+    tester.update_frames(2);
+
+    let composition = &inspect(tester.world())[0];
+    assert_eq!(composition.composes, 2);
+    assert_eq!(composition.listeners, 1);
+    assert_eq!(composition.spawns.len(), 2);
+    assert_eq!(composition.spawns[0].composes, 1);
+    assert_eq!(composition.spawns[1].composes, 2);
+
+    let ticker = composition
+        .composables
+        .iter()
+        .find(|composable| composable.name == "Ticker")
+        .unwrap();
+    assert_eq!(ticker.composes, 2);
+}
+
+#[derive(Data)]
+struct Counter;
+
+impl Compose for Counter {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        use_resource::<Ticks>(&cx);
+    }
+}
+
+#[derive(Data)]
+struct Static;
+
+impl Compose for Static {
+    fn compose(_cx: Scope<Self>) -> impl Compose {}
+}
+
+#[derive(Data)]
+struct Dashboard;
+
+impl Compose for Dashboard {
+    fn compose(_cx: Scope<Self>) -> impl Compose {
+        spawn_with(Label(String::from("dashboard")), (Counter, Static))
+    }
+}
+
+#[test]
+fn it_counts_compositions_of_nested_composables_without_spawns() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        ActuatePlugin::default(),
+        DebugPlugin::default(),
+    ))
+    .insert_resource(Ticks(0));
+    let mut tester = CompositionTester::from_app(app, Dashboard);
+
+    let composition = &inspect(tester.world())[0];
+    assert_eq!(
+        composition.composables,
+        [
+            ComposableInfo {
+                name: String::from("Dashboard"),
+                composes: 1,
+            },
+            ComposableInfo {
+                name: String::from("Spawn"),
+                composes: 1,
+            },
+        ]
+    );
+    assert_eq!(
+        composition.spawns[0].composables,
+        [
+            ComposableInfo {
+                name: String::from("Counter"),
+                composes: 1,
+            },
+            ComposableInfo {
+                name: String::from("Static"),
+                composes: 1,
+            },
+        ]
+    );
+
+    tester.world_mut().resource_mut::<Ticks>().0 = 1;
+    tester.update_frames(2);
+
+    let composition = &inspect(tester.world())[0];
+    assert_eq!(composition.composes, 1);
+    assert_eq!(composition.spawns[0].content_composes, 2);
+    assert_eq!(
+        composition.spawns[0].composables,
+        [
+            ComposableInfo {
+                name: String::from("Counter"),
+                composes: 2,
+            },
+            ComposableInfo {
+                name: String::from("Static"),
+                composes: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn it_only_inspects_with_the_debug_plugin() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, ActuatePlugin::default()));
+    let tester = CompositionTester::from_app(app, Panel);
+
+    assert!(inspect(tester.world()).is_empty());
+    assert!(!tester.world().contains_resource::<CompositionTree>());
+}
+
+#[test]
+fn it_updates_the_composition_tree() {
+    let mut tester = tester();
+    assert_eq!(
+        tester.world().resource::<CompositionTree>().compositions,
+        inspect(tester.world())
+    );
+
+    let root = tester.root();
+    tester
+        .world_mut()
+        .entity_mut(root)
+        .remove::<Composition<Panel>>();
+    tester.update_frames(2);
+    assert!(inspect(tester.world()).is_empty());
+    assert!(tester
+        .world()
+        .resource::<CompositionTree>()
+        .compositions
+        .is_empty());
+}
@@ -0,0 +1,1062 @@
+use actuate::prelude::{Mut, *};
+use bevy::ecs::system::SystemId;
+use bevy::prelude::*;
+use bevy_mod_actuate::{prelude::*, use_commands, use_world_once};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+#[derive(Component)]
+struct Health(u32);
+
+#[derive(Component, Clone, PartialEq, Debug)]
+struct Label(String);
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(ActuatePlugin::default());
+    app
+}
+
+fn labels(app: &mut App) -> Vec<String> {
+    app.world_mut()
+        .query::<&Label>()
+        .iter(app.world())
+        .map(|label| label.0.clone())
+        .collect()
+}
+
+#[derive(Data)]
+struct HealthList {
+    composes: Arc<AtomicUsize>,
+}
+
+impl Compose for HealthList {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.me().composes.fetch_add(1, Ordering::SeqCst);
+
+        let healths = use_query::<&Health, (), _>(&cx, |health| health.0);
+
+        spawn(Label(format!("{:?}", *healths)))
+    }
+}
+
+#[test]
+fn it_recomposes_queries_on_change() {
+    let mut app = app();
+    let composes = Arc::new(AtomicUsize::new(0));
+    let entity = app.world_mut().spawn(Health(1)).id();
+    app.world_mut().spawn(Composition::new(HealthList {
+        composes: composes.clone(),
+    }));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["[1]"]);
+    assert_eq!(composes.load(Ordering::SeqCst), 1);
+
+    app.update();
+    app.update();
+    assert_eq!(composes.load(Ordering::SeqCst), 1);
+
+    app.world_mut().get_mut::<Health>(entity).unwrap().0 = 2;
+    app.update();
+    assert_eq!(labels(&mut app), ["[2]"]);
+
+    app.world_mut().spawn(Health(3));
+    app.update();
+    assert_eq!(labels(&mut app), ["[2, 3]"]);
+
+    app.world_mut().despawn(entity);
+    app.update();
+    assert_eq!(labels(&mut app), ["[3]"]);
+}
+
+#[derive(Resource, Clone)]
+struct Score(u32);
+
+#[derive(Data)]
+struct ScoreBoard {
+    composes: Arc<AtomicUsize>,
+}
+
+impl Compose for ScoreBoard {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.me().composes.fetch_add(1, Ordering::SeqCst);
+
+        let score = use_resource::<Score>(&cx);
+
+        spawn(Label(format!("{:?}", score.as_ref().map(|score| score.0))))
+    }
+}
+
+#[test]
+fn it_recomposes_resources_on_change() {
+    let mut app = app();
+    let composes = Arc::new(AtomicUsize::new(0));
+    app.world_mut().spawn(Composition::new(ScoreBoard {
+        composes: composes.clone(),
+    }));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["None"]);
+
+    app.insert_resource(Score(1));
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(1)"]);
+
+    let count = composes.load(Ordering::SeqCst);
+    app.update();
+    app.update();
+    assert_eq!(composes.load(Ordering::SeqCst), count);
+
+    app.world_mut().resource_mut::<Score>().0 = 2;
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(2)"]);
+
+    app.world_mut().remove_resource::<Score>();
+    app.update();
+    assert_eq!(labels(&mut app), ["None"]);
+}
+
+#[derive(Data)]
+struct HealthTotal {
+    composes: Arc<AtomicUsize>,
+}
+
+impl Compose for HealthTotal {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.me().composes.fetch_add(1, Ordering::SeqCst);
+
+        let total = use_world_memo(&cx, |query: Query<&Health>| {
+            query.iter().map(|health| health.0).sum::<u32>()
+        });
+
+        spawn(Label(total.to_string()))
+    }
+}
+
+#[test]
+fn it_recomposes_memos_on_change() {
+    let mut app = app();
+    let composes = Arc::new(AtomicUsize::new(0));
+    let a = app.world_mut().spawn(Health(1)).id();
+    let b = app.world_mut().spawn(Health(2)).id();
+    app.world_mut().spawn(Composition::new(HealthTotal {
+        composes: composes.clone(),
+    }));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["3"]);
+
+    let count = composes.load(Ordering::SeqCst);
+    app.world_mut().get_mut::<Health>(a).unwrap().0 = 2;
+    app.world_mut().get_mut::<Health>(b).unwrap().0 = 1;
+    app.update();
+    app.update();
+    assert_eq!(composes.load(Ordering::SeqCst), count);
+
+    app.world_mut().spawn(Health(4));
+    app.update();
+    assert_eq!(labels(&mut app), ["7"]);
+}
+
+#[derive(Data)]
+struct ScoreWatcher {
+    runs: Arc<AtomicUsize>,
+}
+
+impl Compose for ScoreWatcher {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let runs = cx.me().runs.clone();
+        use_world_if(&cx, resource_changed::<Score>, move |_: Res<Score>| {
+            runs.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+}
+
+#[test]
+fn it_runs_listeners_on_condition() {
+    let mut app = app();
+    let runs = Arc::new(AtomicUsize::new(0));
+    app.insert_resource(Score(0));
+    app.world_mut()
+        .spawn(Composition::new(ScoreWatcher { runs: runs.clone() }));
+
+    app.update();
+    app.update();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    app.update();
+    app.update();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+    app.world_mut().resource_mut::<Score>().0 = 1;
+    app.update();
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+}
+
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+#[derive(Data)]
+struct Logger {
+    name: &'static str,
+    priority: i32,
+    log: Log,
+}
+
+impl Compose for Logger {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let name = cx.me().name;
+        let log = cx.me().log.clone();
+        use_world_with_priority(&cx, cx.me().priority, move || {
+            log.lock().unwrap().push(name)
+        });
+    }
+}
+
+#[derive(Resource, Clone)]
+struct ShowB(bool);
+
+#[derive(Data)]
+struct Loggers {
+    log: Log,
+}
+
+impl Compose for Loggers {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let show_b = use_resource::<ShowB>(&cx);
+        let logger = |name, priority| Logger {
+            name,
+            priority,
+            log: cx.me().log.clone(),
+        };
+
+        (
+            logger("low", -1),
+            logger("a", 0),
+            show_b.as_ref().unwrap().0.then(|| logger("b", 0)),
+            logger("c", 0),
+            logger("high", 1),
+        )
+    }
+}
+
+fn log_frame(app: &mut App, log: &Log) -> Vec<&'static str> {
+    log.lock().unwrap().clear();
+    app.update();
+    log.lock().unwrap().clone()
+}
+
+#[test]
+fn it_runs_listeners_in_order() {
+    let mut app = app();
+    let log = Log::default();
+    app.insert_resource(ShowB(true));
+    app.world_mut()
+        .spawn(Composition::new(Loggers { log: log.clone() }));
+
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["high", "a", "b", "c", "low"]);
+
+    app.insert_resource(ShowB(false));
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["high", "a", "c", "low"]);
+
+    // Re-mounted scopes register their listeners after the existing ones with the same priority.
+    app.insert_resource(ShowB(true));
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["high", "a", "c", "b", "low"]);
+}
+
+#[derive(Resource, Clone)]
+struct BPriority(i32);
+
+#[derive(Data)]
+struct ReprioritizedLoggers {
+    log: Log,
+}
+
+impl Compose for ReprioritizedLoggers {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let b_priority = use_resource::<BPriority>(&cx);
+        let logger = |name, priority| Logger {
+            name,
+            priority,
+            log: cx.me().log.clone(),
+        };
+
+        (
+            logger("a", 0),
+            logger("b", b_priority.as_ref().unwrap().0),
+            logger("c", 0),
+        )
+    }
+}
+
+#[test]
+fn it_reorders_listeners_on_priority_change() {
+    let mut app = app();
+    let log = Log::default();
+    app.insert_resource(BPriority(0));
+    app.world_mut()
+        .spawn(Composition::new(ReprioritizedLoggers { log: log.clone() }));
+
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["a", "b", "c"]);
+
+    app.insert_resource(BPriority(1));
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["b", "a", "c"]);
+
+    app.insert_resource(BPriority(-1));
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["a", "c", "b"]);
+
+    // Listeners moved back to a priority run after the existing ones.
+    app.insert_resource(BPriority(0));
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["a", "c", "b"]);
+}
+
+#[derive(Data)]
+struct ConditionalLoggers {
+    log: Log,
+}
+
+impl Compose for ConditionalLoggers {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let logger = |name| {
+            let log = cx.me().log.clone();
+            move || log.lock().unwrap().push(name)
+        };
+
+        use_world_if_with_priority(&cx, -1, resource_exists::<Score>, logger("low"));
+        use_world_if_with_priority(&cx, 1, resource_exists::<Score>, logger("high"));
+        use_world(&cx, logger("default"));
+    }
+}
+
+#[test]
+fn it_runs_conditional_listeners_in_order() {
+    let mut app = app();
+    let log = Log::default();
+    app.world_mut()
+        .spawn(Composition::new(ConditionalLoggers { log: log.clone() }));
+
+    app.update();
+    assert_eq!(log_frame(&mut app, &log), ["default"]);
+
+    app.insert_resource(Score(0));
+    assert_eq!(log_frame(&mut app, &log), ["high", "default", "low"]);
+}
+
+#[derive(Data)]
+struct ScoreButton;
+
+impl Compose for ScoreButton {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let score = use_resource_mut::<Score>(&cx);
+
+        use_ref(&cx, || score.update(|score| score.0 += 1));
+
+        spawn(Label(format!("{:?}", score.as_ref().map(|score| score.0))))
+    }
+}
+
+#[test]
+fn it_writes_resources() {
+    let mut app = app();
+    app.insert_resource(Score(1));
+    app.world_mut().spawn(Composition::new(ScoreButton));
+
+    app.update();
+    assert_eq!(app.world().resource::<Score>().0, 2);
+
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(2)"]);
+}
+
+#[derive(Data)]
+struct ScoreClicker;
+
+impl Compose for ScoreClicker {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let score = use_resource_mut::<Score>(&cx);
+
+        spawn(Label(format!("{:?}", score.as_ref().map(|score| score.0))))
+            .observe(move |_: In<Trigger<Ping>>| score.update(|score| score.0 += 1))
+    }
+}
+
+/// Trigger a `Ping` on the entity with a `Label`.
+fn click(app: &mut App) {
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Label>>()
+        .single(app.world());
+    app.world_mut().trigger_targets(Ping, entity);
+}
+
+#[test]
+fn it_writes_resources_from_observers() {
+    let mut app = app();
+    app.insert_resource(Score(1));
+    app.world_mut().spawn(Composition::new(ScoreClicker));
+
+    app.update();
+    click(&mut app);
+    app.update();
+    assert_eq!(app.world().resource::<Score>().0, 2);
+
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(2)"]);
+}
+
+#[derive(Data)]
+struct Clicker {
+    applied: Arc<AtomicUsize>,
+}
+
+impl Compose for Clicker {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let clicks = use_mut(&cx, String::new);
+        let applied = cx.me().applied.clone();
+
+        spawn(Label(clicks.to_string())).observe(move |_: In<Trigger<Ping>>| {
+            let applied = applied.clone();
+            Mut::update(clicks, move |clicks| {
+                applied.fetch_add(1, Ordering::SeqCst);
+                clicks.push('!');
+            })
+        })
+    }
+}
+
+#[test]
+fn it_updates_from_observers_in_their_own_app() {
+    let applied = Arc::new(AtomicUsize::new(0));
+    let mut a = app();
+    let mut b = app();
+    a.world_mut().spawn(Composition::new(Clicker {
+        applied: applied.clone(),
+    }));
+    b.world_mut().spawn(Composition::new(Clicker {
+        applied: applied.clone(),
+    }));
+
+    b.update();
+    a.update();
+
+    click(&mut b);
+    b.update();
+    a.update();
+    assert_eq!(labels(&mut a), [""]);
+    assert_eq!(labels(&mut b), ["!"]);
+    assert_eq!(applied.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn it_discards_observer_updates_of_despawned_compositions() {
+    let applied = Arc::new(AtomicUsize::new(0));
+    let mut app = app();
+    let root = app
+        .world_mut()
+        .spawn(Composition::new(Clicker {
+            applied: applied.clone(),
+        }))
+        .id();
+    for _ in 0..8 {
+        app.world_mut().spawn(Composition::new(Clicker {
+            applied: applied.clone(),
+        }));
+    }
+
+    app.update();
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Label>>()
+        .iter(app.world())
+        .find(|entity| app.world().get::<Parent>(*entity).unwrap().get() == root)
+        .unwrap();
+    app.world_mut().trigger_targets(Ping, entity);
+    app.world_mut().despawn(root);
+    app.update();
+
+    assert_eq!(labels(&mut app), [""; 8]);
+    assert_eq!(applied.load(Ordering::SeqCst), 0);
+}
+
+#[derive(Component, Clone)]
+struct Mana(u32);
+
+#[derive(Data)]
+struct ManaBar {
+    entity: Entity,
+    composes: Arc<AtomicUsize>,
+}
+
+impl Compose for ManaBar {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.me().composes.fetch_add(1, Ordering::SeqCst);
+
+        let mana = use_component::<Mana>(&cx, cx.me().entity);
+
+        spawn(Label(format!("{:?}", mana.as_ref().map(|mana| mana.0))))
+    }
+}
+
+#[test]
+fn it_recomposes_components_on_change() {
+    let mut app = app();
+    let composes = Arc::new(AtomicUsize::new(0));
+    let entity = app.world_mut().spawn(Mana(1)).id();
+    app.world_mut().spawn(Composition::new(ManaBar {
+        entity,
+        composes: composes.clone(),
+    }));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(1)"]);
+
+    let count = composes.load(Ordering::SeqCst);
+    app.world_mut().spawn(Mana(5));
+    app.update();
+    app.update();
+    assert_eq!(composes.load(Ordering::SeqCst), count);
+
+    app.world_mut().get_mut::<Mana>(entity).unwrap().0 = 2;
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(2)"]);
+
+    app.world_mut().entity_mut(entity).remove::<Mana>();
+    app.update();
+    assert_eq!(labels(&mut app), ["None"]);
+
+    app.world_mut().entity_mut(entity).insert(Mana(3));
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(3)"]);
+
+    app.world_mut().despawn(entity);
+    app.update();
+    assert_eq!(labels(&mut app), ["None"]);
+}
+
+#[derive(Data)]
+struct ManaEditor {
+    entity: Entity,
+    composes: Arc<AtomicUsize>,
+}
+
+impl Compose for ManaEditor {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        cx.me().composes.fetch_add(1, Ordering::SeqCst);
+
+        let mana = use_bound_component::<Mana>(&cx, cx.me().entity);
+
+        use_ref(&cx, || mana.update(|mana| mana.0 += 10));
+
+        spawn(Label(format!("{:?}", mana.as_ref().map(|mana| mana.0))))
+    }
+}
+
+#[test]
+fn it_binds_components() {
+    let mut app = app();
+    let composes = Arc::new(AtomicUsize::new(0));
+    let entity = app.world_mut().spawn(Mana(1)).id();
+    app.world_mut().spawn(Composition::new(ManaEditor {
+        entity,
+        composes: composes.clone(),
+    }));
+
+    app.update();
+    app.update();
+    assert_eq!(app.world().get::<Mana>(entity).unwrap().0, 11);
+    assert_eq!(labels(&mut app), ["Some(11)"]);
+
+    let count = composes.load(Ordering::SeqCst);
+    app.update();
+    app.update();
+    assert_eq!(composes.load(Ordering::SeqCst), count);
+
+    app.world_mut().get_mut::<Mana>(entity).unwrap().0 = 2;
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(2)"]);
+    assert_eq!(app.world().get::<Mana>(entity).unwrap().0, 2);
+}
+
+#[derive(Data)]
+struct ManaDrain {
+    entity: Entity,
+}
+
+impl Compose for ManaDrain {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let mana = use_bound_component::<Mana>(&cx, cx.me().entity);
+
+        use_ref(&cx, || {
+            mana.update(|mana| mana.0 -= 1);
+            mana.update(|mana| mana.0 -= 1);
+        });
+
+        spawn(Label(format!("{:?}", mana.as_ref().map(|mana| mana.0))))
+    }
+}
+
+#[test]
+fn it_applies_each_bound_update() {
+    let mut app = app();
+    let entity = app.world_mut().spawn(Mana(10)).id();
+    app.world_mut()
+        .spawn(Composition::new(ManaDrain { entity }));
+
+    app.update();
+    app.update();
+    assert_eq!(app.world().get::<Mana>(entity).unwrap().0, 8);
+    assert_eq!(labels(&mut app), ["Some(8)"]);
+}
+
+#[derive(Data)]
+struct ManaSlider {
+    entity: Entity,
+}
+
+impl Compose for ManaSlider {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let mana = use_bound_component::<Mana>(&cx, cx.me().entity);
+
+        spawn(Label(format!("{:?}", mana.as_ref().map(|mana| mana.0))))
+            .observe(move |_: In<Trigger<Ping>>| mana.set(Mana(42)))
+    }
+}
+
+#[test]
+fn it_binds_components_from_observers() {
+    let mut app = app();
+    let entity = app.world_mut().spawn(Mana(1)).id();
+    app.world_mut()
+        .spawn(Composition::new(ManaSlider { entity }));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(1)"]);
+
+    click(&mut app);
+    app.update();
+    assert_eq!(app.world().get::<Mana>(entity).unwrap().0, 42);
+    assert_eq!(labels(&mut app), ["Some(42)"]);
+}
+
+#[test]
+fn it_binds_components_from_observers_of_their_own_composition() {
+    let mut a = app();
+    let mut b = app();
+    let a_entity = a.world_mut().spawn(Mana(1)).id();
+    let b_entity = b.world_mut().spawn(Mana(1)).id();
+    a.world_mut()
+        .spawn(Composition::new(ManaSlider { entity: a_entity }));
+    b.world_mut()
+        .spawn(Composition::new(ManaSlider { entity: b_entity }));
+
+    b.update();
+    a.update();
+
+    click(&mut b);
+    b.update();
+    a.update();
+    assert_eq!(b.world().get::<Mana>(b_entity).unwrap().0, 42);
+    assert_eq!(labels(&mut b), ["Some(42)"]);
+    assert_eq!(a.world().get::<Mana>(a_entity).unwrap().0, 1);
+    assert_eq!(labels(&mut a), ["Some(1)"]);
+}
+
+#[derive(Event)]
+struct Ping;
+
+#[derive(Event)]
+struct Pong;
+
+#[derive(Data)]
+struct Pinger;
+
+impl Compose for Pinger {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let pings = use_mut(&cx, || 0);
+        let pong_writer = use_event_writer::<Pong>(&cx);
+
+        use_events::<Ping>(&cx, move |_| {
+            Mut::update(pings, |pings| *pings += 1);
+            pong_writer.send(Pong);
+        });
+
+        spawn(Label(pings.to_string()))
+    }
+}
+
+#[test]
+fn it_reads_and_writes_events() {
+    let mut app = app();
+    app.add_event::<Ping>().add_event::<Pong>();
+    app.world_mut().send_event(Ping);
+    app.world_mut().spawn(Composition::new(Pinger));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["0"]);
+
+    app.world_mut().send_event(Ping);
+    app.world_mut().send_event(Ping);
+    app.update();
+    assert_eq!(labels(&mut app), ["2"]);
+    assert_eq!(app.world().resource::<Events<Pong>>().len(), 2);
+}
+
+#[derive(Data)]
+struct PongButton;
+
+impl Compose for PongButton {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let pong_writer = use_event_writer::<Pong>(&cx);
+
+        spawn(Label(String::from("pong")))
+            .observe(move |_: In<Trigger<Ping>>| pong_writer.send(Pong))
+    }
+}
+
+#[test]
+fn it_writes_events_from_observers() {
+    let mut app = app();
+    app.add_event::<Pong>();
+    app.world_mut().spawn(Composition::new(PongButton));
+
+    app.update();
+    click(&mut app);
+    app.update();
+    assert_eq!(app.world().resource::<Events<Pong>>().len(), 1);
+}
+
+#[derive(States, Clone, PartialEq, Eq, Hash, Debug, Default)]
+enum Screen {
+    #[default]
+    Menu,
+    Game,
+}
+
+#[derive(Data)]
+struct ScreenContent {
+    name: &'static str,
+    drops: Arc<AtomicUsize>,
+}
+
+impl Compose for ScreenContent {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let drops = cx.me().drops.clone();
+        use_drop(&cx, move || {
+            drops.fetch_add(1, Ordering::SeqCst);
+        });
+
+        spawn(Label(cx.me().name.to_string()))
+    }
+}
+
+#[derive(Data)]
+struct Screens {
+    drops: Arc<AtomicUsize>,
+}
+
+impl Compose for Screens {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let screen = use_bevy_state::<Screen>(&cx);
+
+        use_ref(&cx, || screen.set(Screen::Game));
+
+        let drops = cx.me().drops.clone();
+        match_state(move |screen: &Screen| ScreenContent {
+            name: match screen {
+                Screen::Menu => "menu",
+                Screen::Game => "game",
+            },
+            drops: drops.clone(),
+        })
+    }
+}
+
+#[test]
+fn it_matches_states() {
+    let mut app = app();
+    app.add_plugins(bevy::state::app::StatesPlugin)
+        .init_state::<Screen>();
+
+    // Bevy 0.15's multi-threaded executor (used by the state transition schedules) violates
+    // Stacked Borrows, as it reborrows each system mutably while it's still borrowed by its task.
+    #[cfg(miri)]
+    for (_, schedule) in app.world_mut().resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(bevy::ecs::schedule::ExecutorKind::SingleThreaded);
+    }
+    let drops = Arc::new(AtomicUsize::new(0));
+    app.world_mut().spawn(Composition::new(Screens {
+        drops: drops.clone(),
+    }));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["menu"]);
+    assert_eq!(drops.load(Ordering::SeqCst), 0);
+
+    app.update();
+    app.update();
+    assert_eq!(*app.world().resource::<State<Screen>>().get(), Screen::Game);
+    assert_eq!(labels(&mut app), ["game"]);
+    assert_eq!(drops.load(Ordering::SeqCst), 1);
+
+    app.world_mut()
+        .resource_mut::<NextState<Screen>>()
+        .set(Screen::Menu);
+    app.update();
+    app.update();
+    assert_eq!(labels(&mut app), ["menu"]);
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
+
+#[derive(Data)]
+struct PlayButton;
+
+impl Compose for PlayButton {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let screen = use_bevy_state::<Screen>(&cx);
+
+        spawn(Label(format!("{:?}", *screen)))
+            .observe(move |_: In<Trigger<Ping>>| screen.set(Screen::Game))
+    }
+}
+
+#[test]
+fn it_sets_states_from_observers() {
+    let mut app = app();
+    app.insert_resource(State::new(Screen::Menu))
+        .init_resource::<NextState<Screen>>();
+    app.world_mut().spawn(Composition::new(PlayButton));
+
+    app.update();
+    assert_eq!(labels(&mut app), ["Some(Menu)"]);
+
+    click(&mut app);
+    app.update();
+    assert!(matches!(
+        *app.world().resource::<NextState<Screen>>(),
+        NextState::Pending(Screen::Game)
+    ));
+}
+
+#[derive(Resource, Default)]
+struct Runs(usize);
+
+#[derive(Resource)]
+struct Systems(SystemId);
+
+#[derive(Data)]
+struct Commander {
+    target: Entity,
+}
+
+impl Compose for Commander {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let commands = use_commands(&cx);
+        let system = *use_world_once(&cx, |systems: Res<Systems>| systems.0);
+        let target = cx.me().target;
+
+        use_events::<Ping>(&cx, move |_| {
+            commands.spawn(Label(String::from("spawned")));
+            commands.entity(target).insert(Mana(7)).remove::<Health>();
+            commands.trigger_targets(Pong, target);
+            commands.run_system(system);
+        });
+    }
+}
+
+#[test]
+fn it_queues_commands() {
+    let mut app = app();
+    app.add_event::<Ping>().init_resource::<Runs>();
+
+    let system = app
+        .world_mut()
+        .register_system(|mut runs: ResMut<Runs>| runs.0 += 1);
+    app.insert_resource(Systems(system));
+
+    let target = app
+        .world_mut()
+        .spawn(Health(1))
+        .observe(|_: Trigger<Pong>, mut runs: ResMut<Runs>| runs.0 += 10)
+        .id();
+    app.world_mut()
+        .spawn(Composition::new(Commander { target }));
+
+    app.update();
+    app.world_mut().send_event(Ping);
+    app.update();
+
+    assert_eq!(labels(&mut app), ["spawned"]);
+    assert_eq!(app.world().get::<Mana>(target).unwrap().0, 7);
+    assert!(app.world().get::<Health>(target).is_none());
+    assert_eq!(app.world().resource::<Runs>().0, 11);
+}
+
+#[derive(Data)]
+struct SpawnButton;
+
+impl Compose for SpawnButton {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let commands = use_commands(&cx);
+
+        spawn(Label(String::from("spawn"))).observe(move |_: In<Trigger<Ping>>| {
+            let entity = commands.spawn(Mana(1)).id().unwrap();
+            commands.entity(entity).insert(Health(2));
+        })
+    }
+}
+
+#[test]
+fn it_queues_commands_from_observers() {
+    let mut app = app();
+    app.world_mut().spawn(Composition::new(SpawnButton));
+
+    app.update();
+    click(&mut app);
+    app.update();
+
+    let (mana, health) = app
+        .world_mut()
+        .query::<(&Mana, &Health)>()
+        .single(app.world());
+    assert_eq!((mana.0, health.0), (1, 2));
+}
+
+#[derive(Data)]
+struct SpawnCallbacks;
+
+impl Compose for SpawnCallbacks {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let commands = use_commands(&cx);
+
+        spawn(Label(String::from("callbacks")))
+            .on_spawn(move |_| {
+                commands.spawn(Mana(1)).insert(Health(1));
+            })
+            .on_update(move |_| {
+                commands.spawn(Mana(2));
+            })
+    }
+}
+
+fn manas(app: &mut App) -> Vec<(u32, Option<u32>)> {
+    let mut manas: Vec<_> = app
+        .world_mut()
+        .query::<(&Mana, Option<&Health>)>()
+        .iter(app.world())
+        .map(|(mana, health)| (mana.0, health.map(|health| health.0)))
+        .collect();
+    manas.sort();
+    manas
+}
+
+#[test]
+fn it_queues_commands_from_spawn_callbacks() {
+    let mut app = app();
+    app.world_mut().spawn(Composition::new(SpawnCallbacks));
+
+    app.update();
+    assert_eq!(manas(&mut app), [(1, Some(1)), (2, None)]);
+}
+
+#[derive(Data)]
+struct SystemSpawner;
+
+impl Compose for SystemSpawner {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let commands = use_commands(&cx);
+        let mana = use_system(&cx, || 3);
+
+        use_ref(&cx, || {
+            mana.run_then(move |mana| {
+                let entity = commands.spawn(Mana(mana)).id().unwrap();
+                commands.entity(entity).insert(Health(4));
+            })
+        });
+    }
+}
+
+#[test]
+fn it_queues_commands_from_system_continuations() {
+    let mut app = app();
+    app.world_mut().spawn(Composition::new(SystemSpawner));
+
+    app.update();
+    assert_eq!(manas(&mut app), [(3, Some(4))]);
+}
+
+#[derive(Data)]
+struct Adder;
+
+impl Compose for Adder {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let total = use_mut(&cx, || None);
+        let add = use_system(&cx, |In(x): In<usize>, mut runs: ResMut<Runs>| {
+            runs.0 += x;
+            runs.0
+        });
+
+        spawn(Label(format!("{:?}", *total))).observe(move |_: In<Trigger<Ping>>| {
+            add.run_with_then(5, move |output| Mut::set(total, Some(output)))
+        })
+    }
+}
+
+fn systems(app: &mut App) -> usize {
+    app.world_mut()
+        .query::<&bevy::ecs::system::SystemIdMarker>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn it_runs_systems() {
+    let mut app = app();
+    app.init_resource::<Runs>();
+    let root = app.world_mut().spawn(Composition::new(Adder)).id();
+
+    app.update();
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Label>>()
+        .single(app.world());
+    app.world_mut().trigger_targets(Ping, entity);
+    app.update();
+    app.update();
+
+    assert_eq!(app.world().resource::<Runs>().0, 5);
+    assert_eq!(labels(&mut app), ["Some(5)"]);
+
+    assert_eq!(systems(&mut app), 1);
+
+    app.world_mut().despawn(root);
+    app.update();
+    assert_eq!(systems(&mut app), 0);
+}
+
+#[test]
+fn it_discards_system_runs_of_despawned_compositions() {
+    let mut app = app();
+    app.init_resource::<Runs>();
+    let root = app.world_mut().spawn(Composition::new(Adder)).id();
+
+    app.update();
+    let entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<Label>>()
+        .single(app.world());
+    app.world_mut().trigger_targets(Ping, entity);
+    app.world_mut().despawn(root);
+    app.update();
+
+    assert_eq!(app.world().resource::<Runs>().0, 0);
+    assert_eq!(systems(&mut app), 0);
+}
@@ -0,0 +1,172 @@
+use actuate::prelude::{Mut, *};
+use bevy::prelude::*;
+use bevy_mod_actuate::{prelude::*, testing::CompositionTester};
+
+#[derive(Component, Clone, PartialEq, Debug)]
+struct Label(String);
+
+#[derive(Event)]
+struct Reset;
+
+#[derive(Data)]
+struct Counter;
+
+impl Compose for Counter {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let count = use_mut(&cx, || 0);
+
+        spawn_with(
+            Name::new("counter"),
+            (
+                spawn((Name::new("label"), Label(count.to_string()))),
+                spawn(Name::new("increment")).observe(
+                    move |_trigger: In<Trigger<Pointer<Click>>>| Mut::update(count, |x| *x += 1),
+                ),
+                spawn(Name::new("reset"))
+                    .observe(move |_trigger: In<Trigger<Reset>>| Mut::set(count, 0)),
+            ),
+        )
+    }
+}
+
+#[test]
+fn it_finds_spawned_entities() {
+    let tester = CompositionTester::new(Counter);
+
+    let counter = tester.find_by_name("counter").unwrap();
+    assert_eq!(tester.children(tester.root()), [counter]);
+
+    let names: Vec<_> = tester
+        .entities()
+        .into_iter()
+        .map(|entity| tester.get::<Name>(entity).unwrap().to_string())
+        .collect();
+    assert_eq!(names, ["counter", "label", "increment", "reset"]);
+
+    assert_eq!(
+        tester.find::<Label>(),
+        [tester.find_by_name("label").unwrap()]
+    );
+    assert_eq!(tester.find_by_name("missing"), None);
+}
+
+#[test]
+fn it_simulates_triggers() {
+    let mut tester = CompositionTester::new(Counter);
+    let label = tester.find_by_name("label").unwrap();
+    let increment = tester.find_by_name("increment").unwrap();
+    let reset = tester.find_by_name("reset").unwrap();
+
+    tester.click(increment).click(increment).update();
+    assert_eq!(tester.get::<Label>(label), Some(&Label("2".to_string())));
+
+    tester.trigger(reset, Reset).update();
+    assert_eq!(tester.get::<Label>(label), Some(&Label("0".to_string())));
+}
+
+#[test]
+fn it_despawns_content_with_the_root() {
+    let mut tester = CompositionTester::new(Counter);
+    assert_eq!(tester.entities().len(), 4);
+
+    let root = tester.root();
+    tester
+        .world_mut()
+        .entity_mut(root)
+        .remove::<Composition<Counter>>();
+    tester.update_frames(2);
+    assert!(tester.entities().is_empty());
+}
+
+#[test]
+fn it_matches_snapshots() {
+    let mut tester = CompositionTester::new(Counter);
+    tester.assert_snapshot("tests/snapshots/counter.ron");
+
+    // Values aren't part of the structural snapshot.
+    let increment = tester.find_by_name("increment").unwrap();
+    tester.click(increment).update();
+    tester.assert_snapshot("tests/snapshots/counter.ron");
+}
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+struct Score(u32);
+
+#[derive(Data)]
+struct ScoreBoard;
+
+impl Compose for ScoreBoard {
+    fn compose(_cx: Scope<Self>) -> impl Compose {
+        spawn_with(Score(1), (spawn(Score(2)), spawn(Label(String::new()))))
+    }
+}
+
+#[test]
+fn it_snapshots_values() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, ActuatePlugin::default()))
+        .register_type::<Score>();
+    let tester = CompositionTester::from_app(app, ScoreBoard);
+
+    assert_eq!(
+        tester.snapshot_with_values(),
+        r#"[
+    Entity(
+        components: {
+            "testing::Score": (1),
+        },
+        children: [
+            Entity(
+                components: {
+                    "testing::Score": (2),
+                },
+                children: [],
+            ),
+            Entity(
+                components: {
+                    "testing::Label": (),
+                },
+                children: [],
+            ),
+        ],
+    ),
+]
+"#
+    );
+}
+
+#[derive(Component, Clone, Reflect)]
+#[reflect(Component)]
+#[type_path = "game"]
+struct Level;
+
+#[derive(Data)]
+struct LevelView;
+
+impl Compose for LevelView {
+    fn compose(_cx: Scope<Self>) -> impl Compose {
+        spawn(Level).observe(|_trigger: In<Trigger<Reset>>| {})
+    }
+}
+
+#[test]
+fn it_names_registered_components_by_type_path() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, ActuatePlugin::default()))
+        .register_type::<Level>();
+    let tester = CompositionTester::from_app(app, LevelView);
+
+    assert_eq!(
+        tester.snapshot(),
+        r#"[
+    Entity(
+        components: [
+            "game::Level",
+        ],
+        children: [],
+    ),
+]
+"#
+    );
+}
@@ -0,0 +1,99 @@
+use actuate::prelude::{Mut, *};
+use bevy::prelude::*;
+use bevy_mod_actuate::{prelude::*, use_world_once};
+
+#[derive(Component, Clone)]
+struct Item;
+
+#[derive(Component, Clone)]
+struct Existing;
+
+#[derive(Resource)]
+struct Shown(bool);
+
+#[derive(Resource, Default)]
+struct Found(usize);
+
+#[derive(Data)]
+struct Toggle;
+
+impl Compose for Toggle {
+    fn compose(cx: Scope<Self>) -> impl Compose {
+        let is_shown = use_mut(&cx, || true);
+
+        use_world(&cx, move |shown: Res<Shown>| {
+            if shown.0 != *is_shown {
+                Mut::set(is_shown, shown.0)
+            }
+        });
+
+        let found = *use_world_once(&cx, |query: Query<&Existing>| query.iter().count());
+        use_world(&cx, move |mut res: ResMut<Found>| res.0 = found);
+
+        spawn_with(
+            Item,
+            (
+                spawn_with(Item, spawn(Item)),
+                if *is_shown {
+                    Some(spawn_with(Item, spawn(Item)))
+                } else {
+                    None
+                },
+            ),
+        )
+    }
+}
+
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(ActuatePlugin::default())
+        .insert_resource(Shown(true))
+        .init_resource::<Found>();
+    app
+}
+
+fn items(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<Item>>()
+        .iter(app.world())
+        .count()
+}
+
+#[test]
+fn it_spawns_nested_content() {
+    let mut app = app();
+    app.world_mut().spawn_batch([Existing, Existing]);
+    app.world_mut().spawn(Composition::new(Toggle));
+
+    app.update();
+    app.update();
+
+    assert_eq!(items(&mut app), 5);
+    assert_eq!(app.world().resource::<Found>().0, 2);
+}
+
+#[test]
+fn it_despawns_content_while_composing() {
+    let mut app = app();
+    app.world_mut().spawn(Composition::new(Toggle));
+    app.update();
+
+    app.world_mut().resource_mut::<Shown>().0 = false;
+    app.update();
+    app.update();
+    assert_eq!(items(&mut app), 3);
+
+    app.world_mut().resource_mut::<Shown>().0 = true;
+    app.update();
+    app.update();
+    assert_eq!(items(&mut app), 5);
+}
+
+#[test]
+fn it_drops_compositions_with_the_world() {
+    let mut app = app();
+    app.world_mut().spawn(Composition::new(Toggle));
+    app.update();
+
+    drop(app);
+}